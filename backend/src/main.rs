@@ -33,12 +33,57 @@ fn main() {
             state::commands::get_mod_details,
             state::commands::get_mods,
             state::commands::add_current_mod,
+            state::commands::update_with_current_mod,
+            state::commands::rollback_mod,
+            state::commands::get_version_retention,
+            state::commands::set_version_retention,
+            state::commands::get_new_mod_collisions,
             state::commands::remove_mod,
+            state::commands::undo_profile_edit,
+            state::commands::redo_profile_edit,
             state::commands::apply,
+            state::commands::validate_dependencies,
+            state::commands::get_available_dependencies,
+            state::commands::add_dependencies,
             state::commands::set_mod_enabled_state,
+            state::commands::set_mod_notes,
+            state::commands::move_mod,
+            state::commands::move_mods,
+            state::commands::sort_mods,
+            state::commands::get_load_order_rules,
+            state::commands::set_load_order_rules,
+            state::commands::get_masterlist_findings,
+            state::commands::import_masterlist,
+            state::commands::import_bg3mm_load_order,
+            state::commands::export_bg3mm_load_order,
+            state::commands::get_load_order_code,
+            state::commands::import_load_order_code,
+            state::commands::export_modpack,
+            state::commands::import_modpack,
+            state::commands::build_profile_from_manifest,
+            state::commands::write_profile_manifest,
+            state::commands::get_installed_game_mods,
+            state::commands::import_game_mods,
+            state::commands::dismiss_game_mods_import,
             state::commands::create_profile,
+            state::commands::rename_profile,
+            state::commands::duplicate_profile,
+            state::commands::delete_profile,
+            state::commands::add_child_profile,
+            state::commands::set_profile_parent,
+            state::commands::compare_profiles,
+            state::commands::merge_profiles,
             state::commands::switch_profile,
             state::commands::get_profiles,
+            state::commands::get_guid_conflicts,
+            state::commands::get_file_conflicts,
+            state::commands::set_conflict_resolution,
+            state::commands::get_vanilla_overrides,
+            state::commands::find_mods_providing,
+            state::commands::search_library_files,
+            state::commands::get_library_mods,
+            state::commands::add_library_mod,
+            state::commands::delete_library_mod,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
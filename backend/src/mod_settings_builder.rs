@@ -16,8 +16,7 @@ impl ModSettingsBuilder {
         save.add_child(version).unwrap();
 
         let mods_node = Self::build_mods_node(mod_metas, gustav_dev_meta);
-        let mut mod_order_node = XMLElement::new("node");
-        mod_order_node.add_attribute("id", "ModOrder");
+        let mod_order_node = Self::build_mod_order_node(mod_metas, gustav_dev_meta);
 
         let mut children = XMLElement::new("children");
         children.add_child(mod_order_node).unwrap();
@@ -39,6 +38,34 @@ impl ModSettingsBuilder {
         xml
     }
 
+    fn build_mod_order_node(mod_metas: &[ModState], gustav_dev_meta: &Meta) -> XMLElement {
+        let mut children = XMLElement::new("children");
+
+        children.add_child(Self::build_module(gustav_dev_meta)).unwrap();
+
+        for mod_state in mod_metas {
+            if !mod_state.enabled { continue }
+            if let Some(meta) = mod_state.meta.as_ref() {
+                children.add_child(Self::build_module(meta)).unwrap();
+            }
+        }
+
+        let mut mod_order_node = XMLElement::new("node");
+        mod_order_node.add_attribute("id", "ModOrder");
+        mod_order_node.add_child(children).unwrap();
+
+        mod_order_node
+    }
+
+    fn build_module(mod_meta: &Meta) -> XMLElement {
+        let uuid = Self::build_mod_meta_attribute("UUID", mod_meta.uuid());
+
+        let mut module = XMLElement::new("node");
+        module.add_attribute("id", "Module");
+        module.add_child(uuid).unwrap();
+        module
+    }
+
     fn build_mods_node(mod_metas: &[ModState], gustav_dev_meta: &Meta) -> XMLElement {
         let mut children = XMLElement::new("children");
 
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+use models::{CollisionKind, ConflictingMod, ModCollision};
+use package_helper::Meta;
+use crate::state::mod_models::ModState;
+use crate::state::State;
+
+/// Finds mods sharing a UUID or Folder, the game loads only one of them and which one is unpredictable
+pub(crate) struct CollisionDetector;
+
+impl CollisionDetector {
+    pub fn find_collisions(mods: &[ModState]) -> Vec<ModCollision> {
+        let mut collisions = Vec::new();
+        for kind in [CollisionKind::Uuid, CollisionKind::Folder] {
+            let mut groups: BTreeMap<String, Vec<ConflictingMod>> = BTreeMap::new();
+            for (index, mod_state) in mods.iter().enumerate() {
+                if !mod_state.enabled { continue }
+                let Some(meta) = mod_state.meta.as_ref() else { continue };
+                groups.entry(Self::key(meta, kind)).or_default().push(Self::conflicting_mod(mods, index));
+            }
+
+            for (value, group) in groups {
+                if group.len() < 2 { continue }
+                // Mods sharing a UUID usually share their Folder too
+                if collisions.iter().any(|collision: &ModCollision| collision.mods == group) { continue }
+                collisions.push(ModCollision { kind, value, mods: group });
+            }
+        }
+        collisions
+    }
+
+    /// Collisions of a mod about to be added with the mods of the profile
+    pub fn find_collisions_with(mods: &[ModState], new_meta: &Meta) -> Vec<ModCollision> {
+        [CollisionKind::Uuid, CollisionKind::Folder].into_iter()
+            .filter_map(|kind| {
+                let value = Self::key(new_meta, kind);
+                let colliding_mods = mods.iter().enumerate()
+                    .filter(|(_, mod_state)| mod_state.meta.as_ref().is_some_and(|meta| Self::key(meta, kind) == value))
+                    .map(|(index, _)| Self::conflicting_mod(mods, index))
+                    .collect::<Vec<ConflictingMod>>();
+                (!colliding_mods.is_empty()).then_some(ModCollision { kind, value, mods: colliding_mods })
+            })
+            .collect()
+    }
+
+    fn key(meta: &Meta, kind: CollisionKind) -> String {
+        match kind {
+            CollisionKind::Uuid => meta.uuid().value().to_ascii_lowercase(),
+            CollisionKind::Folder => meta.folder().value().to_ascii_lowercase(),
+        }
+    }
+
+    fn conflicting_mod(mods: &[ModState], index: usize) -> ConflictingMod {
+        ConflictingMod {
+            index,
+            name: State::meta_to_mod_details(mods[index].meta.as_ref(), &mods[index].path, true).name,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use std::path::PathBuf;
-use models::{MMResult, Mod, ModDetailsError};
+use models::{ApplyError, MergeChange, ProfileDiff, ProfileDiffError, ProfileManifestError, ModpackError, LoadOrderExchangeError, MissingMod, GameModsImportError, GameModsImportMode, InstalledGameMod, ModCollision, AvailableDependency, ConflictAnalysisError, FileConflict, GuidConflict, LibraryError, LibraryFile, LibraryMod, LoadOrderRule, LoadOrderSortError, MasterlistError, MasterlistFinding, MMResult, Mod, ModDependencyProblems, ModDetailsError, VanillaOverrides, VanillaOverridesError};
 use crate::state::State;
 
 #[tauri::command(rename_all = "snake_case", async)]
@@ -8,8 +8,33 @@ pub fn get_mods() -> Vec<Mod> {
 }
 
 #[tauri::command(rename_all = "snake_case", async)]
-pub fn add_current_mod() {
-    State::add_current_mod();
+pub fn add_current_mod() -> Option<usize> {
+    State::add_current_mod()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn update_with_current_mod(index: usize) -> Option<usize> {
+    State::update_with_current_mod(index)
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn rollback_mod(index: usize, version_index: usize) {
+    State::rollback_mod(index, version_index);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_version_retention() -> usize {
+    State::get_version_retention()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn set_version_retention(retention: usize) {
+    State::set_version_retention(retention);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_new_mod_collisions() -> Vec<ModCollision> {
+    State::get_new_mod_collisions()
 }
 
 #[tauri::command(rename_all = "snake_case", async)]
@@ -18,8 +43,120 @@ pub fn remove_mod(index: usize) {
 }
 
 #[tauri::command(rename_all = "snake_case", async)]
-pub fn apply() {
-    State::apply();
+pub fn undo_profile_edit() -> bool {
+    State::undo_profile_edit()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn redo_profile_edit() -> bool {
+    State::redo_profile_edit()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn apply(confirmed: bool) -> MMResult<(), ApplyError> {
+    State::apply(confirmed).into()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn validate_dependencies() -> Vec<ModDependencyProblems> {
+    State::validate_dependencies()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn set_mod_notes(index: usize, notes: String) {
+    State::set_mod_notes(index, notes);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn move_mod(index: usize, position: usize) {
+    State::move_mods(index, 1, position);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn move_mods(start: usize, count: usize, position: usize) {
+    State::move_mods(start, count, position);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_available_dependencies(index: usize) -> Vec<AvailableDependency> {
+    State::get_available_dependencies(index)
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn add_dependencies(index: usize) -> usize {
+    State::add_dependencies(index)
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_masterlist_findings() -> Vec<MasterlistFinding> {
+    State::get_masterlist_findings()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn import_masterlist(path: PathBuf) -> MMResult<(), MasterlistError> {
+    State::import_masterlist(&path).into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn import_bg3mm_load_order(path: PathBuf) -> MMResult<Vec<MissingMod>, LoadOrderExchangeError> {
+    State::import_bg3mm_load_order(&path).into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn export_bg3mm_load_order(path: PathBuf) -> MMResult<(), LoadOrderExchangeError> {
+    State::export_bg3mm_load_order(&path).into()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_load_order_code() -> String {
+    State::get_load_order_code()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn import_load_order_code(code: String) -> MMResult<Vec<MissingMod>, LoadOrderExchangeError> {
+    State::import_load_order_code(&code).into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn export_modpack(path: PathBuf) -> MMResult<(), ModpackError> {
+    State::export_modpack(&path).into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn import_modpack(path: PathBuf) -> MMResult<(), ModpackError> {
+    State::import_modpack(&path).into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn build_profile_from_manifest(path: PathBuf) -> MMResult<Vec<MissingMod>, ProfileManifestError> {
+    State::build_profile_from_manifest(&path).into()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn write_profile_manifest(path: Option<PathBuf>) -> MMResult<(), ProfileManifestError> {
+    State::write_profile_manifest(path).into()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_load_order_rules(index: usize) -> Vec<LoadOrderRule> {
+    State::get_load_order_rules(index)
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn set_load_order_rules(index: usize, rules: Vec<LoadOrderRule>) {
+    State::set_load_order_rules(index, rules);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn sort_mods() -> MMResult<(), LoadOrderSortError> {
+    State::sort_mods().into()
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -33,11 +170,62 @@ pub fn set_mod_enabled_state(index: usize, enabled: bool) {
     State::set_mod_enabled_state(index, enabled);
 }
 
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_installed_game_mods() -> Vec<InstalledGameMod> {
+    State::get_installed_game_mods()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn import_game_mods(mode: GameModsImportMode) -> MMResult<(), GameModsImportError> {
+    State::import_game_mods(mode).into()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn dismiss_game_mods_import() {
+    State::dismiss_game_mods_import();
+}
+
 #[tauri::command(rename_all = "snake_case", async)]
 pub fn create_profile(name: String) {
     State::create_profile(name);
 }
 
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn rename_profile(index: usize, name: String) {
+    State::rename_profile(index, name);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn duplicate_profile(index: usize, name: String) {
+    State::duplicate_profile(index, name);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn delete_profile(index: usize) {
+    State::delete_profile(index);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn add_child_profile(parent: usize, name: String) {
+    State::add_child_profile(parent, name);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn set_profile_parent(profile: usize, parent: Option<usize>) {
+    State::set_profile_parent(profile, parent);
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn compare_profiles(a: usize, b: usize) -> MMResult<ProfileDiff, ProfileDiffError> {
+    State::compare_profiles(a, b).into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn merge_profiles(from: usize, into: usize, changes: Vec<MergeChange>) -> MMResult<(), ProfileDiffError> {
+    State::merge_profiles(from, into, &changes).into()
+}
+
 #[tauri::command(rename_all = "snake_case", async)]
 pub fn switch_profile(index: usize) {
     State::switch_profile(index);
@@ -46,4 +234,55 @@ pub fn switch_profile(index: usize) {
 #[tauri::command(rename_all = "snake_case", async)]
 pub fn get_profiles() -> models::Profiles {
     State::get_profiles()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_guid_conflicts() -> MMResult<Vec<GuidConflict>, ConflictAnalysisError> {
+    State::get_guid_conflicts().into()
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_file_conflicts() -> MMResult<Vec<FileConflict>, ConflictAnalysisError> {
+    State::get_file_conflicts().into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn set_conflict_resolution(file_path: String, mod_index: Option<usize>) {
+    State::set_conflict_resolution(&file_path, mod_index);
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_vanilla_overrides(game_data_dir: PathBuf) -> MMResult<Vec<VanillaOverrides>, VanillaOverridesError> {
+    State::get_vanilla_overrides(&game_data_dir).into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn find_mods_providing(file_path: String) -> Vec<LibraryFile> {
+    State::find_mods_providing(&file_path)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn search_library_files(pattern: String) -> Vec<LibraryFile> {
+    State::search_library_files(&pattern)
+}
+
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn get_library_mods() -> Vec<LibraryMod> {
+    State::get_library_mods()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn add_library_mod(path: PathBuf) -> MMResult<usize, LibraryError> {
+    State::add_library_mod(&path).into()
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[tauri::command(rename_all = "snake_case", async)]
+pub fn delete_library_mod(path: PathBuf) -> MMResult<(), LibraryError> {
+    State::delete_library_mod(&path).into()
 }
\ No newline at end of file
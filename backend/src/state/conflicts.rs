@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use models::{ConflictAnalysisError, ConflictingMod, FileConflict, GuidConflict};
+use package_helper::{Package, PackageReader, Resource};
+use crate::{error, warn};
+use crate::state::content_index::ContentIndex;
+use crate::state::State;
+
+/// An enabled mod of the current profile, analysed mods are always passed in load order
+pub(crate) struct AnalysedMod {
+    pub index: usize,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl From<&AnalysedMod> for ConflictingMod {
+    fn from(value: &AnalysedMod) -> Self {
+        ConflictingMod {
+            index: value.index,
+            name: value.name.clone(),
+        }
+    }
+}
+
+struct GuidDefinition<'a> {
+    attribute: String,
+    node: String,
+    providers: Vec<&'a AnalysedMod>,
+}
+
+struct FileProviders<'a> {
+    path: String,
+    providers: Vec<&'a AnalysedMod>,
+}
+
+pub(crate) struct ConflictAnalyser;
+
+impl ConflictAnalyser {
+    const GUID_ATTRIBUTES: [&'static str; 2] = ["MapKey", "UUID"];
+
+    pub fn find_guid_conflicts(mods: &[AnalysedMod]) -> Result<Vec<GuidConflict>, ConflictAnalysisError> {
+        let mut definitions: BTreeMap<String, GuidDefinition> = BTreeMap::new();
+
+        for analysed_mod in mods {
+            let package = Self::read_package(analysed_mod)?;
+            let resource_files = package.read_files(|file| {
+                // meta.lsx only references the mod itself and its dependencies
+                Resource::is_resource_file(file.name()) && !file.name().to_ascii_lowercase().ends_with("/meta.lsx")
+            }).map_err(|error| {
+                error!("Cannot read resource files of '{}': {error:?}", analysed_mod.name);
+                ConflictAnalysisError::CannotReadPackagedFiles { mod_name: analysed_mod.name.clone() }
+            })?;
+
+            let mut defined_guids = HashSet::new();
+            for (file_info, contents) in resource_files {
+                let resource = match Resource::read(file_info.name(), &contents) {
+                    Ok(resource) => resource,
+                    Err(error) => {
+                        warn!("Skipping unreadable resource '{}' in '{}': {error:?}", file_info.name(), analysed_mod.name);
+                        continue;
+                    }
+                };
+
+                for node in resource.nodes() {
+                    for attribute in node.attributes() {
+                        if !Self::GUID_ATTRIBUTES.contains(&attribute.id()) { continue }
+                        let Some(guid) = attribute.value().filter(|guid| !guid.is_empty()) else { continue };
+
+                        let guid = guid.to_ascii_lowercase();
+                        if !defined_guids.insert(guid.clone()) { continue }
+
+                        definitions.entry(guid).or_insert_with(|| GuidDefinition {
+                            attribute: attribute.id().to_string(),
+                            node: node.name().to_string(),
+                            providers: Vec::new(),
+                        }).providers.push(analysed_mod);
+                    }
+                }
+            }
+        }
+
+        Ok(definitions.into_iter()
+            .filter(|(_, definition)| definition.providers.len() > 1)
+            .map(|(guid, definition)| GuidConflict {
+                guid,
+                attribute: definition.attribute,
+                node: definition.node,
+                // Mods later in the load order override earlier ones
+                winner: ConflictingMod::from(*definition.providers.last().unwrap()),
+                providers: definition.providers.into_iter().map(ConflictingMod::from).collect(),
+            })
+            .collect())
+    }
+
+    pub fn find_file_conflicts(mods: &[AnalysedMod], resolutions: &HashMap<String, PathBuf>,
+                               index: &ContentIndex) -> Result<Vec<FileConflict>, ConflictAnalysisError> {
+        let mut files: BTreeMap<String, FileProviders> = BTreeMap::new();
+
+        for analysed_mod in mods {
+            let indexed_mod = index.get(&analysed_mod.path).ok_or_else(|| {
+                error!("Mod '{}' is not in the content index", analysed_mod.name);
+                ConflictAnalysisError::CannotFindPackageFile { mod_name: analysed_mod.name.clone() }
+            })?;
+            for indexed_file in &indexed_mod.files {
+                let file = files.entry(indexed_file.name.to_ascii_lowercase()).or_insert_with(|| FileProviders {
+                    path: indexed_file.name.clone(),
+                    providers: Vec::new(),
+                });
+                if file.providers.last().map(|provider| provider.index) != Some(analysed_mod.index) {
+                    file.providers.push(analysed_mod);
+                }
+            }
+        }
+
+        Ok(files.into_iter()
+            .filter(|(_, file)| file.providers.len() > 1)
+            .map(|(key, file)| {
+                let chosen = resolutions.get(&key)
+                    .and_then(|chosen_path| file.providers.iter().find(|provider| provider.path == *chosen_path));
+                FileConflict {
+                    path: file.path,
+                    resolved: chosen.is_some(),
+                    // Mods later in the load order override earlier ones
+                    winner: ConflictingMod::from(*chosen.unwrap_or(file.providers.last().unwrap())),
+                    providers: file.providers.into_iter().map(ConflictingMod::from).collect(),
+                }
+            })
+            .collect())
+    }
+
+    pub fn read_package(analysed_mod: &AnalysedMod) -> Result<Package, ConflictAnalysisError> {
+        let pak_path = State::find_pak_path(&analysed_mod.path).ok_or_else(|| {
+            error!("Cannot find package file of '{}'", analysed_mod.name);
+            ConflictAnalysisError::CannotFindPackageFile { mod_name: analysed_mod.name.clone() }
+        })?;
+
+        PackageReader::read_package(&pak_path).map_err(|error| {
+            error!("Cannot read package of '{}': {error:?}", analysed_mod.name);
+            ConflictAnalysisError::CannotReadPackage { mod_name: analysed_mod.name.clone() }
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use models::LibraryFile;
+use package_helper::{Meta, PackageReader};
+use crate::{error, info, warn};
+use crate::state::helpers::{HashHelper, PathHelper};
+use crate::state::State;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct IndexedFile {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct IndexedMod {
+    pub pak_path: PathBuf,
+    pub size: u64,
+    pub modified: u64,
+    pub hash: String,
+    pub files: Vec<IndexedFile>,
+    pub metas: Vec<Meta>,
+}
+
+/// File lists, hashes and metas of every stored mod, keyed by the mod's data dir
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct ContentIndex {
+    #[serde(default)]
+    format: u32,
+    mods: HashMap<PathBuf, IndexedMod>,
+}
+
+impl ContentIndex {
+    const FILE_NAME: &'static str = "content_index.json";
+    /// Bumped whenever indexed data gains information, so mods are indexed again
+    const FORMAT: u32 = 2;
+
+    pub fn load() -> ContentIndex {
+        let Ok(index) = fs::read_to_string(PathHelper::get_data_dir().join(Self::FILE_NAME)) else {
+            info!("No content index found");
+            return ContentIndex::default();
+        };
+
+        match serde_json::from_str::<ContentIndex>(&index) {
+            Ok(index) if index.format == Self::FORMAT => index,
+            Ok(_) => {
+                info!("Content index is outdated and will be rebuilt");
+                ContentIndex::default()
+            },
+            Err(error) => {
+                warn!("Content index is invalid and will be rebuilt: {error}");
+                ContentIndex::default()
+            },
+        }
+    }
+
+    /// Re-indexes mods whose package changed size or modification time and forgets removed mods
+    pub fn refresh<'a>(&mut self, mod_paths: impl Iterator<Item = &'a Path>) {
+        let mut changed = false;
+        let mut refreshed_mods = HashMap::with_capacity(self.mods.len());
+
+        for mod_path in mod_paths {
+            if refreshed_mods.contains_key(mod_path) { continue }
+
+            let Some((pak_path, size, modified)) = Self::package_signature(mod_path) else {
+                warn!("Cannot index mod without a package {}", mod_path.to_string_lossy());
+                continue;
+            };
+
+            let indexed_mod = match self.mods.remove(mod_path) {
+                Some(indexed_mod) if indexed_mod.pak_path == pak_path && indexed_mod.size == size
+                    && indexed_mod.modified == modified => indexed_mod,
+                _ => {
+                    changed = true;
+                    let Some(indexed_mod) = Self::index_mod(pak_path, size, modified) else { continue };
+                    indexed_mod
+                }
+            };
+            refreshed_mods.insert(mod_path.to_owned(), indexed_mod);
+        }
+
+        changed |= !self.mods.is_empty() || self.format != Self::FORMAT;
+        self.mods = refreshed_mods;
+        self.format = Self::FORMAT;
+
+        if changed {
+            self.save();
+        }
+    }
+
+    pub fn get(&self, mod_path: &Path) -> Option<&IndexedMod> {
+        self.mods.get(mod_path)
+    }
+
+    pub fn find_providers(&self, file_path: &str) -> Vec<LibraryFile> {
+        self.find_files(|name| name.eq_ignore_ascii_case(file_path))
+    }
+
+    /// Supports `*` and `?` within a path segment and `**` across segments, matching is case insensitive
+    pub fn search(&self, pattern: &str) -> Vec<LibraryFile> {
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    regex.push_str(".*");
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+
+        let regex = match RegexBuilder::new(&regex).case_insensitive(true).build() {
+            Ok(regex) => regex,
+            Err(error) => {
+                error!("Invalid file search pattern '{pattern}': {error}");
+                return Vec::new();
+            }
+        };
+        self.find_files(|name| regex.is_match(name))
+    }
+
+    fn find_files(&self, matches: impl Fn(&str) -> bool) -> Vec<LibraryFile> {
+        let mut found_files = self.mods.iter()
+            .flat_map(|(mod_path, indexed_mod)| indexed_mod.files.iter()
+                .filter(|file| matches(&file.name))
+                .map(move |file| LibraryFile {
+                    mod_name: State::meta_to_mod_details(indexed_mod.metas.first(), mod_path, true).name,
+                    mod_path: mod_path.clone(),
+                    path: file.name.clone(),
+                    size: file.size,
+                }))
+            .collect::<Vec<LibraryFile>>();
+        found_files.sort_by(|file0, file1| file0.path.cmp(&file1.path).then(file0.mod_name.cmp(&file1.mod_name)));
+        found_files
+    }
+
+    fn package_signature(mod_path: &Path) -> Option<(PathBuf, u64, u64)> {
+        let pak_path = State::find_pak_path(mod_path)?;
+        let metadata = fs::metadata(&pak_path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        Some((pak_path, metadata.len(), modified))
+    }
+
+    fn index_mod(pak_path: PathBuf, size: u64, modified: u64) -> Option<IndexedMod> {
+        info!("Indexing {}", pak_path.to_string_lossy());
+        let package = match PackageReader::read_package(&pak_path) {
+            Ok(package) => package,
+            Err(error) => {
+                error!("Cannot index package {}: {error:?}", pak_path.to_string_lossy());
+                return None;
+            }
+        };
+        let hash = match HashHelper::hash_file(&pak_path) {
+            Ok(hash) => hash,
+            Err(error) => {
+                error!("Cannot hash package {}: {error}", pak_path.to_string_lossy());
+                return None;
+            }
+        };
+        let metas = package.get_meta().unwrap_or_else(|error| {
+            warn!("Cannot read metas of {}: {error:?}", pak_path.to_string_lossy());
+            Vec::new()
+        });
+
+        Some(IndexedMod {
+            files: package.files().iter().map(|file| IndexedFile {
+                name: file.name().to_string(),
+                // Uncompressed files may not record an uncompressed size
+                size: file.uncompressed_size().max(file.size_on_disk()) as u64,
+            }).collect(),
+            pak_path,
+            size,
+            modified,
+            hash,
+            metas,
+        })
+    }
+
+    fn save(&self) {
+        let index = match serde_json::to_string(self) {
+            Ok(index) => index,
+            Err(error) => {
+                error!("Could not serialize content index: {error:?}");
+                return;
+            }
+        };
+
+        if let Err(error) = fs::write(PathHelper::get_data_dir().join(Self::FILE_NAME), index) {
+            error!("Could not save content index: {error:?}");
+        }
+    }
+}
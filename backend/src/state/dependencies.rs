@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use models::{AvailableDependency, ConflictingMod, DependencyProblem, DependencyProblemKind, DependencySource, ModDependencyProblems};
+use package_helper::{Dependency, Meta, Version};
+use crate::state::mod_models::ModState;
+use crate::state::State;
+
+pub(crate) struct DependencyValidator;
+
+impl DependencyValidator {
+    /// Modules shipped with the game, mods commonly list them as dependencies
+    const GAME_MODULE_FOLDERS: [&'static str; 11] = [
+        "GustavDev", "Gustav", "GustavX", "Shared", "SharedDev", "Engine", "FW3", "Honour", "HonourX", "MainUI", "ModBrowser",
+    ];
+
+    pub fn validate(mods: &[ModState]) -> Vec<ModDependencyProblems> {
+        let mut installed: HashMap<String, Vec<&ModState>> = HashMap::new();
+        for mod_state in mods {
+            let Some(meta) = mod_state.meta.as_ref() else { continue };
+            installed.entry(meta.uuid().value().to_ascii_lowercase()).or_default().push(mod_state);
+        }
+
+        mods.iter().enumerate()
+            .filter(|(_, mod_state)| mod_state.enabled)
+            .filter_map(|(index, mod_state)| {
+                let meta = mod_state.meta.as_ref()?;
+                let problems = meta.dependencies().iter()
+                    .filter(|dependency| !Self::is_game_module(dependency))
+                    .filter_map(|dependency| Some(DependencyProblem {
+                        uuid: dependency.uuid().to_string(),
+                        name: if dependency.name().is_empty() { dependency.uuid().to_string() } else { dependency.name().to_string() },
+                        kind: Self::check_dependency(dependency, &installed)?,
+                    }))
+                    .collect::<Vec<DependencyProblem>>();
+
+                if problems.is_empty() { return None }
+                Some(ModDependencyProblems {
+                    dependent_mod: ConflictingMod {
+                        index,
+                        name: State::meta_to_mod_details(Some(meta), &mod_state.path, true).name,
+                    },
+                    problems,
+                })
+            })
+            .collect()
+    }
+
+    /// Dependencies of the mod at `index` that are disabled in the profile or missing from it but in the `library`
+    pub fn find_available(mods: &[ModState], index: usize, library: &[(PathBuf, Meta)]) -> Vec<AvailableDependency> {
+        let Some(meta) = mods.get(index).and_then(|mod_state| mod_state.meta.as_ref()) else { return Vec::new() };
+
+        meta.dependencies().iter()
+            .filter(|dependency| !Self::is_game_module(dependency))
+            .filter_map(|dependency| {
+                let uuid = dependency.uuid().to_ascii_lowercase();
+                let provides = |meta: &Meta| meta.uuid().value().eq_ignore_ascii_case(&uuid)
+                    && meta.version().version64() >= dependency.version64();
+
+                let in_profile = mods.iter().enumerate()
+                    .filter(|(_, mod_state)| mod_state.meta.as_ref().is_some_and(|meta| meta.uuid().value().eq_ignore_ascii_case(&uuid)))
+                    .collect::<Vec<(usize, &ModState)>>();
+                let source = if in_profile.is_empty() {
+                    let (mod_path, _) = library.iter()
+                        .filter(|(_, meta)| provides(meta))
+                        .max_by_key(|(_, meta)| meta.version().version64())?;
+                    DependencySource::Library { mod_path: mod_path.clone() }
+                } else {
+                    if in_profile.iter().any(|(_, mod_state)| mod_state.enabled) { return None }
+
+                    let (index, _) = in_profile.iter().find(|(_, mod_state)| mod_state.meta.as_ref().is_some_and(provides))?;
+                    DependencySource::Profile { index: *index }
+                };
+
+                Some(AvailableDependency {
+                    uuid: dependency.uuid().to_string(),
+                    name: if dependency.name().is_empty() { dependency.uuid().to_string() } else { dependency.name().to_string() },
+                    source,
+                })
+            })
+            .collect()
+    }
+
+    fn check_dependency(dependency: &Dependency, installed: &HashMap<String, Vec<&ModState>>) -> Option<DependencyProblemKind> {
+        let Some(candidates) = installed.get(&dependency.uuid().to_ascii_lowercase()) else {
+            return Some(DependencyProblemKind::NotInstalled);
+        };
+
+        let Some(installed_version64) = candidates.iter()
+            .filter(|mod_state| mod_state.enabled)
+            .filter_map(|mod_state| Some(mod_state.meta.as_ref()?.version().version64()))
+            .max() else {
+            return Some(DependencyProblemKind::Disabled);
+        };
+
+        if installed_version64 < dependency.version64() {
+            return Some(DependencyProblemKind::VersionTooOld {
+                installed_version: Version::from_version64(installed_version64).to_string(),
+                required_version: Version::from_version64(dependency.version64()).to_string(),
+            });
+        }
+        None
+    }
+
+    fn is_game_module(dependency: &Dependency) -> bool {
+        Self::GAME_MODULE_FOLDERS.iter().any(|folder| folder.eq_ignore_ascii_case(dependency.folder()))
+    }
+}
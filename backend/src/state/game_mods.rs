@@ -0,0 +1,88 @@
+use std::path::{Path, PathBuf};
+use package_helper::{Meta, PackageReader};
+use crate::{error, warn};
+
+/// A pak the user put into the game's Mods folder themselves
+pub(crate) struct GameMod {
+    pub path: PathBuf,
+    pub meta: Option<Meta>,
+    pub enabled: bool,
+}
+
+/// Finds mods installed before the manager took over the Mods folder and modsettings.lsx
+pub(crate) struct GameMods;
+
+impl GameMods {
+    /// Regular pak files in `mods_dir` in the order modsettings.lsx activates them, followed by the inactive ones
+    pub fn find(mods_dir: &Path, mod_settings_path: &Path) -> Vec<GameMod> {
+        let mod_order = Self::read_mod_order(mod_settings_path);
+
+        let Ok(entries) = std::fs::read_dir(mods_dir) else {
+            warn!("Could not read the Mods folder {}", mods_dir.to_string_lossy());
+            return Vec::new();
+        };
+
+        let mut game_mods = Vec::new();
+        for entry in entries.flatten() {
+            // Symlinks were created by the manager when applying a profile
+            let Ok(file_type) = entry.file_type() else { continue };
+            if !file_type.is_file() || !entry.file_name().to_string_lossy().ends_with(".pak") { continue }
+
+            let path = entry.path();
+            let meta = Self::read_meta(&path);
+            let enabled = meta.as_ref().is_some_and(|meta| mod_order.contains(&meta.uuid().value().to_lowercase()));
+            game_mods.push(GameMod { path, meta, enabled });
+        }
+
+        game_mods.sort_by_key(|game_mod| {
+            let position = game_mod.meta.as_ref()
+                .and_then(|meta| mod_order.iter().position(|uuid| *uuid == meta.uuid().value().to_lowercase()));
+            (position.unwrap_or(usize::MAX), game_mod.path.file_name().map(std::ffi::OsStr::to_os_string))
+        });
+        game_mods
+    }
+
+    pub fn name(game_mod: &GameMod) -> String {
+        match game_mod.meta.as_ref() {
+            Some(meta) => meta.name().value().to_string(),
+            None => game_mod.path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+        }
+    }
+
+    /// Lowercase UUIDs of the active mods, older versions of the game list them in ModOrder and newer ones only in Mods
+    fn read_mod_order(mod_settings_path: &Path) -> Vec<String> {
+        let Ok(mod_settings) = std::fs::read_to_string(mod_settings_path) else {
+            warn!("Could not read {}, treating every installed mod as inactive", mod_settings_path.to_string_lossy());
+            return Vec::new();
+        };
+        let xml = match roxmltree::Document::parse(&mod_settings) {
+            Ok(xml) => xml,
+            Err(error) => {
+                error!("Could not parse {}: {error}", mod_settings_path.to_string_lossy());
+                return Vec::new();
+            }
+        };
+
+        let uuids_in = |node_id: &str, module_id: &str| xml.descendants()
+            .find(|node| node.attribute("id") == Some(node_id))
+            .map(|node| node.descendants()
+                .filter(|module| module.attribute("id") == Some(module_id))
+                .filter_map(|module| module.children().find(|attribute| attribute.attribute("id") == Some("UUID")))
+                .filter_map(|uuid| uuid.attribute("value"))
+                .map(str::to_lowercase)
+                .collect::<Vec<String>>());
+
+        // Settings written without a load order have an empty ModOrder node
+        uuids_in("ModOrder", "Module")
+            .filter(|uuids| !uuids.is_empty())
+            .or_else(|| uuids_in("Mods", "ModuleShortDesc"))
+            .unwrap_or_default()
+    }
+
+    fn read_meta(path: &Path) -> Option<Meta> {
+        let package = PackageReader::read_package(path).map_err(|error| {
+            warn!("Could not read installed package {}: {error:?}", path.to_string_lossy());
+        }).ok()?;
+        package.get_meta().ok()?.drain(..).next()
+    }
+}
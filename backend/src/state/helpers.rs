@@ -1,7 +1,15 @@
-use std::path::PathBuf;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
 
 pub struct PathHelper;
 
+#[cfg(test)]
+thread_local! {
+    static TEST_DATA_DIR: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
 impl PathHelper {
     pub fn find_bg3_app_data() -> PathBuf {
         #[cfg(target_os = "linux")]
@@ -24,14 +32,42 @@ impl PathHelper {
     }
 
     pub fn get_data_dir() -> PathBuf {
+        #[cfg(test)]
+        if let Some(data_dir) = TEST_DATA_DIR.with(|data_dir| data_dir.borrow().clone()) {
+            return data_dir;
+        }
+
         let mut data_dir = dirs::data_local_dir().expect("Could not get local data directory");
         data_dir.push("OneHornModManager");
         data_dir
     }
 
+    /// Keeps tests of the current thread out of the real data dir
+    #[cfg(test)]
+    pub fn set_test_data_dir(data_dir: &Path) {
+        TEST_DATA_DIR.with(|test_data_dir| *test_data_dir.borrow_mut() = Some(data_dir.to_owned()));
+    }
+
     pub fn get_mod_store_dir() -> PathBuf {
         let mut dir = PathHelper::get_data_dir();
         dir.push("Mods");
         dir
     }
-}
\ No newline at end of file
+}
+
+pub struct HashHelper;
+
+impl HashHelper {
+    pub fn hash_file(path: &Path) -> io::Result<String> {
+        let mut file = File::open(path)?;
+        let mut hasher = Sha256::new();
+        io::copy(&mut file, &mut hasher)?;
+
+        Ok(hasher.finalize().iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+
+    /// Whether `value` has the form `hash_file` returns, 64 lowercase hex characters
+    pub fn is_hash(value: &str) -> bool {
+        value.len() == 64 && value.bytes().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+    }
+}
@@ -0,0 +1,166 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+use models::LoadOrderRule;
+use crate::state::mod_models::ModState;
+
+/// Ordering constraints between the mods of a profile, identified by their position in the profile
+pub(crate) struct LoadOrderGraph {
+    /// For every mod the mods that have to load before it
+    load_after: Vec<BTreeSet<usize>>,
+}
+
+impl LoadOrderGraph {
+    pub fn from_dependencies(mods: &[ModState]) -> LoadOrderGraph {
+        let mut graph = LoadOrderGraph {
+            load_after: vec![BTreeSet::new(); mods.len()],
+        };
+
+        let positions = Self::uuid_positions(mods);
+        for (index, mod_state) in mods.iter().enumerate() {
+            let Some(meta) = mod_state.meta.as_ref() else { continue };
+            for dependency in meta.dependencies() {
+                // Dependencies on the game's own modules are always loaded first
+                let Some(&dependency_index) = positions.get(&dependency.uuid().to_ascii_lowercase()) else { continue };
+                graph.add_constraint(dependency_index, index);
+            }
+        }
+
+        graph
+    }
+
+    /// Rules are keyed by the lowercase UUID of the mod they belong to, rules naming mods outside the profile are ignored
+    pub fn add_rules(&mut self, mods: &[ModState], rules: &HashMap<String, Vec<LoadOrderRule>>) {
+        let positions = Self::uuid_positions(mods);
+        let mut pinned_top = Vec::new();
+        let mut pinned_bottom = Vec::new();
+
+        for (uuid, mod_rules) in rules {
+            let Some(&index) = positions.get(uuid) else { continue };
+            for rule in mod_rules {
+                match rule {
+                    LoadOrderRule::LoadAfter { uuid } => if let Some(&other) = positions.get(&uuid.to_ascii_lowercase()) {
+                        self.add_constraint(other, index);
+                    },
+                    LoadOrderRule::LoadBefore { uuid } => if let Some(&other) = positions.get(&uuid.to_ascii_lowercase()) {
+                        self.add_constraint(index, other);
+                    },
+                    LoadOrderRule::PinTop => pinned_top.push(index),
+                    LoadOrderRule::PinBottom => pinned_bottom.push(index),
+                }
+            }
+        }
+
+        // A mod pinned to both ends ends up in a cycle and is reported as contradictory
+        for other in 0..mods.len() {
+            for &index in &pinned_top {
+                if !pinned_top.contains(&other) { self.add_constraint(index, other) }
+            }
+            for &index in &pinned_bottom {
+                if !pinned_bottom.contains(&other) { self.add_constraint(other, index) }
+            }
+        }
+    }
+
+    pub fn add_constraint(&mut self, before: usize, after: usize) {
+        if before != after {
+            self.load_after[after].insert(before);
+        }
+    }
+
+    /// Returns the new order as positions in the current order, mods that are not constrained keep their relative
+    /// order. If the constraints contain a cycle the mods forming it are returned as the error, each mod loading
+    /// after the one following it
+    pub fn sort(&self) -> Result<Vec<usize>, Vec<usize>> {
+        let mut remaining_dependencies = self.load_after.iter().map(BTreeSet::len).collect::<Vec<usize>>();
+        let mut dependents = vec![Vec::new(); self.load_after.len()];
+        for (index, load_after) in self.load_after.iter().enumerate() {
+            for &dependency in load_after {
+                dependents[dependency].push(index);
+            }
+        }
+
+        let mut ready = remaining_dependencies.iter().enumerate()
+            .filter(|(_, &count)| count == 0)
+            .map(|(index, _)| Reverse(index))
+            .collect::<BinaryHeap<Reverse<usize>>>();
+
+        let mut order = Vec::with_capacity(self.load_after.len());
+        while let Some(Reverse(index)) = ready.pop() {
+            order.push(index);
+            for &dependent in &dependents[index] {
+                remaining_dependencies[dependent] -= 1;
+                if remaining_dependencies[dependent] == 0 {
+                    ready.push(Reverse(dependent));
+                }
+            }
+        }
+
+        if order.len() == self.load_after.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&remaining_dependencies))
+        }
+    }
+
+    fn uuid_positions(mods: &[ModState]) -> HashMap<String, usize> {
+        mods.iter().enumerate()
+            .filter_map(|(index, mod_state)| Some((mod_state.meta.as_ref()?.uuid().value().to_ascii_lowercase(), index)))
+            .collect()
+    }
+
+    /// Every mod left unsorted waits on another unsorted mod, so following those leads back into a cycle
+    fn find_cycle(&self, remaining_dependencies: &[usize]) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut current = remaining_dependencies.iter().position(|&count| count > 0).unwrap();
+        while !path.contains(&current) {
+            path.push(current);
+            current = *self.load_after[current].iter()
+                .find(|&&dependency| remaining_dependencies[dependency] > 0)
+                .unwrap();
+        }
+
+        let cycle_start = path.iter().position(|&index| index == current).unwrap();
+        path.split_off(cycle_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use models::LoadOrderRule;
+    use crate::state::test_mods::test_mod;
+    use super::LoadOrderGraph;
+
+    #[test]
+    fn keeps_order_without_constraints() {
+        let mods = ["a", "b", "c", "d"].map(|uuid| test_mod(uuid, &[]));
+        let mut graph = LoadOrderGraph::from_dependencies(&mods);
+        graph.add_rules(&mods, &HashMap::new());
+        assert_eq!(graph.sort(), Ok(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn moves_mods_after_dependencies_and_rules() {
+        // a needs c, b is ruled to load after d, everything else keeps its place
+        let mods = [test_mod("a", &["c", "not-installed"]), test_mod("b", &[]), test_mod("c", &[]), test_mod("d", &[]), test_mod("e", &[])];
+        let mut graph = LoadOrderGraph::from_dependencies(&mods);
+        let rules = HashMap::from([("b".to_string(), vec![LoadOrderRule::LoadAfter { uuid: "D".to_string() }])]);
+        graph.add_rules(&mods, &rules);
+        assert_eq!(graph.sort(), Ok(vec![2, 0, 3, 1, 4]));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        // b and d need each other, a and c are not part of the cycle
+        let mods = [test_mod("a", &[]), test_mod("b", &["d"]), test_mod("c", &[]), test_mod("d", &["b"]), test_mod("e", &["d"])];
+        let graph = LoadOrderGraph::from_dependencies(&mods);
+        let mut cycle = graph.sort().unwrap_err();
+        cycle.sort_unstable();
+        assert_eq!(cycle, [1, 3]);
+
+        let mut graph = LoadOrderGraph::from_dependencies(&mods[..3]);
+        let rules = HashMap::from([("a".to_string(), vec![LoadOrderRule::PinTop, LoadOrderRule::PinBottom])]);
+        graph.add_rules(&mods[..3], &rules);
+        assert!(graph.sort().unwrap_err().contains(&0));
+    }
+}
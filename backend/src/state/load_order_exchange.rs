@@ -0,0 +1,172 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use base64ct::{Base64UrlUnpadded, Encoding};
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+use models::LoadOrderExchangeError;
+use crate::error;
+use crate::state::mod_models::ModState;
+
+/// A mod of a load order shared by UUID
+pub(crate) struct SharedMod {
+    pub uuid: String,
+    pub name: String,
+    /// 0 when the version is not known
+    pub version64: u64,
+    pub enabled: bool,
+}
+
+/// The load order export of BG3 Mod Manager, other fields it writes are ignored
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Bg3ModManagerOrder {
+    pub order: Vec<Bg3ModManagerEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Bg3ModManagerEntry {
+    #[serde(rename = "UUID")]
+    pub uuid: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+impl Bg3ModManagerOrder {
+    pub fn read(path: &Path) -> Result<Bg3ModManagerOrder, LoadOrderExchangeError> {
+        let order = fs::read_to_string(path).map_err(|error| {
+            error!("Could not read load order {}: {error}", path.to_string_lossy());
+            LoadOrderExchangeError::CannotReadFile
+        })?;
+
+        serde_json::from_str(&order).map_err(|error| {
+            error!("Load order {} is invalid: {error}", path.to_string_lossy());
+            LoadOrderExchangeError::InvalidLoadOrder(error.to_string())
+        })
+    }
+
+    pub fn into_shared_mods(self) -> Vec<SharedMod> {
+        self.order.into_iter().map(|entry| SharedMod {
+            uuid: entry.uuid,
+            name: entry.name,
+            version64: 0,
+            enabled: true,
+        }).collect()
+    }
+
+    /// Only enabled mods are exported, like BG3 Mod Manager only exports its active mods
+    pub fn from_mods(mods: &[ModState]) -> Bg3ModManagerOrder {
+        Bg3ModManagerOrder {
+            order: mods.iter()
+                .filter(|mod_state| mod_state.enabled)
+                .filter_map(|mod_state| mod_state.meta.as_ref())
+                .map(|meta| Bg3ModManagerEntry {
+                    uuid: meta.uuid().value().to_string(),
+                    name: meta.name().value().to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), LoadOrderExchangeError> {
+        let order = serde_json::to_string_pretty(self).map_err(|error| {
+            error!("Could not serialize load order: {error}");
+            LoadOrderExchangeError::CannotWriteFile
+        })?;
+
+        fs::write(path, order).map_err(|error| {
+            error!("Could not write load order {}: {error}", path.to_string_lossy());
+            LoadOrderExchangeError::CannotWriteFile
+        })
+    }
+}
+
+/// UUID, Version64, enabled and name, kept as an array to keep codes short
+#[derive(Serialize, Deserialize)]
+struct CodedMod(String, u64, bool, String);
+
+#[derive(Serialize, Deserialize)]
+struct CodedProfile(String, Vec<CodedMod>);
+
+/// Text small enough to paste into a chat, a profile as deflated JSON encoded as URL safe base64
+pub(crate) struct LoadOrderCode;
+
+impl LoadOrderCode {
+    const PREFIX: &'static str = "OHMM1:";
+    /// Decoded codes are far smaller, this only guards against codes that inflate endlessly
+    const MAX_DECODED_SIZE: u64 = 16 * 1024 * 1024;
+
+    pub fn encode(profile_name: &str, mods: &[ModState]) -> String {
+        let profile = CodedProfile(profile_name.to_string(), mods.iter()
+            .filter_map(|mod_state| Some((mod_state.meta.as_ref()?, mod_state.enabled)))
+            .map(|(meta, enabled)| CodedMod(meta.uuid().value().to_string(), meta.version().version64(), enabled, meta.name().value().to_string()))
+            .collect());
+
+        let json = serde_json::to_vec(&profile).expect("Load order codes only contain strings, numbers and flags");
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&json).expect("Writing to memory cannot fail");
+        let compressed = encoder.finish().expect("Writing to memory cannot fail");
+
+        format!("{}{}", Self::PREFIX, Base64UrlUnpadded::encode_string(&compressed))
+    }
+
+    /// Returns the profile name and its mods
+    pub fn decode(code: &str) -> Result<(String, Vec<SharedMod>), LoadOrderExchangeError> {
+        let invalid = |reason: String| {
+            error!("Invalid load order code: {reason}");
+            LoadOrderExchangeError::InvalidLoadOrder(reason)
+        };
+
+        let code = code.trim().strip_prefix(Self::PREFIX).ok_or_else(|| invalid("Not a load order code".into()))?;
+        let compressed = Base64UrlUnpadded::decode_vec(code).map_err(|error| invalid(error.to_string()))?;
+        let mut json = Vec::new();
+        DeflateDecoder::new(compressed.as_slice()).take(Self::MAX_DECODED_SIZE).read_to_end(&mut json)
+            .map_err(|error| invalid(error.to_string()))?;
+        let CodedProfile(name, mods) = serde_json::from_slice(&json).map_err(|error| invalid(error.to_string()))?;
+
+        Ok((name, mods.into_iter().map(|CodedMod(uuid, version64, enabled, name)| SharedMod { uuid, name, version64, enabled }).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use base64ct::{Base64UrlUnpadded, Encoding};
+    use models::LoadOrderExchangeError;
+    use crate::state::mod_models::ModState;
+    use crate::state::test_mods::test_mod;
+    use super::LoadOrderCode;
+
+    #[test]
+    fn decodes_encoded_profile() {
+        let mut disabled = test_mod("b", &[]);
+        disabled.enabled = false;
+        let without_meta = ModState { meta: None, path: PathBuf::from("Mods/c"), enabled: true, notes: String::new(), previous_versions: Vec::new() };
+
+        let code = LoadOrderCode::encode("Profile", &[test_mod("a", &[]), disabled, without_meta]);
+        assert!(code.starts_with(LoadOrderCode::PREFIX));
+
+        let (name, mods) = LoadOrderCode::decode(&format!("  {code}\n")).unwrap();
+        assert_eq!(name, "Profile");
+        let mods = mods.iter().map(|shared_mod| (shared_mod.uuid.as_str(), shared_mod.name.as_str(), shared_mod.version64, shared_mod.enabled)).collect::<Vec<_>>();
+        assert_eq!(mods, [("a", "a", 1 << 55, true), ("b", "b", 1 << 55, false)]);
+    }
+
+    #[test]
+    fn rejects_invalid_codes() {
+        let decode = |code: String| LoadOrderCode::decode(&code);
+        assert!(matches!(decode("not a code".to_string()), Err(LoadOrderExchangeError::InvalidLoadOrder(_))));
+        assert!(matches!(decode(format!("{}not base64!", LoadOrderCode::PREFIX)), Err(LoadOrderExchangeError::InvalidLoadOrder(_))));
+
+        // A final block of the reserved block type
+        let not_deflate = Base64UrlUnpadded::encode_string(&[0xFF; 16]);
+        assert!(matches!(decode(format!("{}{not_deflate}", LoadOrderCode::PREFIX)), Err(LoadOrderExchangeError::InvalidLoadOrder(_))));
+
+        let code = LoadOrderCode::encode("Profile", &[test_mod("a", &[])]);
+        let truncated = &code[..code.len() - 4];
+        assert!(matches!(decode(truncated.to_string()), Err(LoadOrderExchangeError::InvalidLoadOrder(_))));
+    }
+}
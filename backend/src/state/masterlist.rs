@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use serde::Deserialize;
+use models::{ConflictingMod, MasterlistError, MasterlistFinding, MasterlistFindingKind, MasterlistLevel};
+use package_helper::Version;
+use crate::{error, info, warn};
+use crate::state::helpers::PathHelper;
+use crate::state::mod_models::ModState;
+use crate::state::State;
+
+#[derive(Deserialize)]
+struct IncompatibleMods {
+    mods: [String; 2],
+    note: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LoadOrderRelation {
+    before: String,
+    after: String,
+    note: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ModMessage {
+    uuid: String,
+    /// Inclusive
+    min_version: Option<String>,
+    /// Exclusive
+    max_version: Option<String>,
+    level: MasterlistLevel,
+    text: String,
+}
+
+/// Known facts about mods kept by the user in `masterlist.toml` in the data dir, mods are referenced by UUID
+///
+/// ```toml
+/// [[incompatible]]
+/// mods = ["<uuid>", "<uuid>"]
+/// note = "Both replace the camp UI"
+///
+/// [[load_order]]
+/// before = "<uuid>"
+/// after = "<uuid>"
+///
+/// [[messages]]
+/// uuid = "<uuid>"
+/// min_version = "1.0.0.0"
+/// max_version = "1.2.0.0"
+/// level = "warning"
+/// text = "Versions before 1.2 corrupt saves"
+/// ```
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Masterlist {
+    incompatible: Vec<IncompatibleMods>,
+    load_order: Vec<LoadOrderRelation>,
+    messages: Vec<ModMessage>,
+}
+
+impl Masterlist {
+    const FILE_NAME: &'static str = "masterlist.toml";
+
+    pub fn load() -> Masterlist {
+        let Ok(masterlist) = fs::read_to_string(PathHelper::get_data_dir().join(Self::FILE_NAME)) else {
+            return Masterlist::default();
+        };
+
+        toml::from_str(&masterlist).unwrap_or_else(|error| {
+            warn!("Ignoring invalid masterlist: {error}");
+            Masterlist::default()
+        })
+    }
+
+    /// Validates the masterlist at `path` and replaces the current one with it
+    pub fn import(path: &Path) -> Result<(), MasterlistError> {
+        let masterlist = fs::read_to_string(path).map_err(|error| {
+            error!("Could not read masterlist {}: {error}", path.to_string_lossy());
+            MasterlistError::CannotReadFile
+        })?;
+
+        if let Err(error) = toml::from_str::<Masterlist>(&masterlist) {
+            error!("Masterlist {} is invalid: {error}", path.to_string_lossy());
+            return Err(MasterlistError::InvalidMasterlist(error.to_string()));
+        }
+
+        fs::write(PathHelper::get_data_dir().join(Self::FILE_NAME), masterlist).map_err(|error| {
+            error!("Could not save masterlist: {error}");
+            MasterlistError::CannotSaveMasterlist
+        })?;
+        info!("Imported masterlist from {}", path.to_string_lossy());
+        Ok(())
+    }
+
+    /// Findings for the enabled mods of a profile
+    pub fn evaluate(&self, mods: &[ModState]) -> Vec<MasterlistFinding> {
+        let positions = Self::enabled_positions(mods);
+        let mod_at = |index: usize| ConflictingMod {
+            index,
+            name: State::meta_to_mod_details(mods[index].meta.as_ref(), &mods[index].path, true).name,
+        };
+        let mut findings = Vec::new();
+
+        for incompatible in &self.incompatible {
+            let [first, second] = &incompatible.mods;
+            let (Some(&first), Some(&second)) = (positions.get(&first.to_ascii_lowercase()), positions.get(&second.to_ascii_lowercase())) else { continue };
+            for (subject, other) in [(first, second), (second, first)] {
+                findings.push(MasterlistFinding {
+                    subject: mod_at(subject),
+                    level: MasterlistLevel::Error,
+                    kind: MasterlistFindingKind::Incompatible { other: mod_at(other) },
+                    note: incompatible.note.clone(),
+                });
+            }
+        }
+
+        for (before, after, relation) in self.order_relations(&positions) {
+            if before < after { continue }
+            findings.push(MasterlistFinding {
+                subject: mod_at(after),
+                level: MasterlistLevel::Warning,
+                kind: MasterlistFindingKind::WrongOrder { should_load_after: mod_at(before) },
+                note: relation.note.clone(),
+            });
+        }
+
+        for message in &self.messages {
+            let Some(&index) = positions.get(&message.uuid.to_ascii_lowercase()) else { continue };
+            let Some(meta) = mods[index].meta.as_ref() else { continue };
+            if !Self::in_range(meta.version().version64(), message) { continue }
+
+            findings.push(MasterlistFinding {
+                subject: mod_at(index),
+                level: message.level,
+                kind: MasterlistFindingKind::Message,
+                note: Some(message.text.clone()),
+            });
+        }
+
+        findings
+    }
+
+    /// Load order relations between enabled mods as (before, after) positions
+    pub fn load_order_constraints(&self, mods: &[ModState]) -> Vec<(usize, usize)> {
+        let positions = Self::enabled_positions(mods);
+        self.order_relations(&positions).map(|(before, after, _)| (before, after)).collect()
+    }
+
+    fn order_relations<'a>(&'a self, positions: &'a HashMap<String, usize>) -> impl Iterator<Item = (usize, usize, &'a LoadOrderRelation)> {
+        self.load_order.iter().filter_map(|relation| Some((
+            *positions.get(&relation.before.to_ascii_lowercase())?,
+            *positions.get(&relation.after.to_ascii_lowercase())?,
+            relation,
+        )))
+    }
+
+    fn enabled_positions(mods: &[ModState]) -> HashMap<String, usize> {
+        mods.iter().enumerate()
+            .filter(|(_, mod_state)| mod_state.enabled)
+            .filter_map(|(index, mod_state)| Some((mod_state.meta.as_ref()?.uuid().value().to_ascii_lowercase(), index)))
+            .collect()
+    }
+
+    fn in_range(version64: u64, message: &ModMessage) -> bool {
+        let parse = |version: &Option<String>| version.as_deref().and_then(|version| match Version::from_str(version) {
+            Ok(version) => Some(version.version64()),
+            Err(_) => {
+                warn!("Ignoring invalid masterlist version '{version}' for {}", message.uuid);
+                None
+            }
+        });
+
+        parse(&message.min_version).is_none_or(|min| version64 >= min)
+            && parse(&message.max_version).is_none_or(|max| version64 < max)
+    }
+}
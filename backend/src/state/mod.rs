@@ -1,14 +1,28 @@
-use std::fs::{create_dir_all, File, OpenOptions, remove_dir_all, copy};
+use std::collections::HashSet;
+use std::fs::{create_dir_all, File, OpenOptions, remove_dir_all};
 use std::io::{ErrorKind, Read, Write};
 use std::path::{Path, PathBuf};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use spin::{Mutex, MutexGuard};
-use models::{Mod, ModDetailsError};
+use models::{ApplyError, ProfileDiff, ProfileDiffError, ProfileManifestError, ModpackError, LoadOrderExchangeError, MissingMod, GameModsImportError, GameModsImportMode, InstalledGameMod, ModCollision, ApplyWarnings, MasterlistError, MasterlistFinding, MasterlistLevel, AvailableDependency, DependencySource, ConflictAnalysisError, FileConflict, GuidConflict, LibraryError, LibraryFile, LibraryMod, LoadOrderRule, LoadOrderSortError, MergeChange, Mod, ModDependencyProblems, ModDetailsError, VanillaOverrides, VanillaOverridesError};
 use package_helper::{Meta, PackageReader};
 use crate::{debug, error, info, trace, warn};
 use crate::mod_settings_builder::ModSettingsBuilder;
-use crate::state::helpers::PathHelper;
+use crate::state::conflicts::{AnalysedMod, ConflictAnalyser};
+use crate::state::collisions::CollisionDetector;
+use crate::state::content_index::ContentIndex;
+use crate::state::dependencies::DependencyValidator;
+use crate::state::game_mods::{GameMod, GameMods};
+use crate::state::load_order::LoadOrderGraph;
+use crate::state::load_order_exchange::{Bg3ModManagerOrder, LoadOrderCode, SharedMod};
+use crate::state::masterlist::Masterlist;
+use crate::state::modpack::{Modpack, ModpackPackage};
+use crate::state::mod_store::ModStore;
+use crate::state::profile_manifest::{ManifestMod, ProfileManifest};
+use crate::state::resolutions::ResolutionPackage;
+use crate::state::vanilla_index::VanillaIndex;
+use crate::state::helpers::{HashHelper, PathHelper};
 use crate::state::mod_models::SelectedNewModInfo;
 use crate::state::profiles::Profiles;
 
@@ -16,6 +30,24 @@ pub mod commands;
 pub mod mod_models;
 mod helpers;
 mod profiles;
+mod conflicts;
+mod resolutions;
+mod vanilla_index;
+mod content_index;
+mod load_order;
+mod dependencies;
+mod masterlist;
+mod collisions;
+mod game_mods;
+mod load_order_exchange;
+mod modpack;
+mod profile_manifest;
+mod mod_store;
+mod profile_diff;
+mod profile_overlay;
+mod profile_history;
+#[cfg(test)]
+mod test_mods;
 
 static STATE: Lazy<Mutex<State>> = Lazy::new(|| Mutex::new(State::new()));
 
@@ -29,6 +61,15 @@ pub(crate) struct State {
 
     profiles: Profiles,
     gustav_dev_mod_meta: Option<Meta>,
+    /// How many versions a mod was updated from are kept to roll back to
+    #[serde(default = "State::default_version_retention")]
+    version_retention: usize,
+    /// Mods installed without the manager are only offered for import once
+    #[serde(default)]
+    game_mods_import_offered: bool,
+
+    #[serde(skip)]
+    content_index: ContentIndex,
 }
 
 // Loading and saving
@@ -36,109 +77,435 @@ impl State {
     pub fn init() {
         let mut state = State::get();
         state.load();
+        // Mods being added were stored in the old layout, they are moved with the rest of the store
+        if state.profiles.needs_store_migration() {
+            state.selected_new_mod_info = None;
+        }
         state.bg3_appdata = PathHelper::find_bg3_app_data().to_string_lossy().to_string();
         state.profiles.init();
+        state.content_index = ContentIndex::load();
+        state.refresh_content_index();
+        state.refresh_metas();
     }
 
-    pub fn apply() {
+    /// Unless `confirmed`, nothing is applied while there are dependency problems or masterlist warnings
+    pub fn apply(confirmed: bool) -> Result<(), ApplyError> {
         let mut state = State::get();
+        state.profiles.resolve_current_profile();
+
+        if !confirmed {
+            let mods = state.profiles.get_mods();
+            let warnings = ApplyWarnings {
+                collisions: CollisionDetector::find_collisions(mods),
+                dependency_problems: DependencyValidator::validate(mods),
+                masterlist_findings: Masterlist::load().evaluate(mods).into_iter()
+                    .filter(|finding| finding.level >= MasterlistLevel::Warning)
+                    .collect(),
+            };
+            if !warnings.collisions.is_empty() || !warnings.dependency_problems.is_empty() || !warnings.masterlist_findings.is_empty() {
+                warn!("Not applying, found {} collisions, {} mods with dependency problems and {} masterlist warnings",
+                    warnings.collisions.len(), warnings.dependency_problems.len(), warnings.masterlist_findings.len());
+                return Err(ApplyError::NeedsConfirmation(warnings));
+            }
+        }
 
-        info!("Creating symlinks to mod pak files");
-        let mut mods_folder_path = PathBuf::from(&state.bg3_appdata);
-        mods_folder_path.push("Mods/");
+        state.apply_mods();
+        Ok(())
+    }
 
-        for entry in std::fs::read_dir(&mods_folder_path).unwrap() {
-            if entry.is_err() { continue }
-            let entry = entry.unwrap();
+    pub fn validate_dependencies() -> Vec<ModDependencyProblems> {
+        DependencyValidator::validate(State::get().profiles.get_mods())
+    }
 
-            if let Ok(file_type) = entry.file_type() {
-                if file_type.is_symlink() {
-                    symlink::remove_symlink_auto(entry.path()).unwrap();
-                }
-            }
+    pub fn get_mods() -> Vec<Mod> {
+        let state = State::get();
+
+        state.profiles.get_mods().iter().map(|mod_state| Mod {
+            notes: mod_state.notes.clone(),
+            previous_versions: mod_state.previous_versions.iter()
+                .map(|version| version.meta.as_ref().map(|meta| meta.version().to_string()).unwrap_or_default())
+                .collect(),
+            ..State::meta_to_mod_details(mod_state.meta.as_ref(), &PathBuf::from(&mod_state.path), mod_state.enabled)
+        }).collect::<Vec<Mod>>()
+    }
+
+    pub fn remove_mod(index: usize) {
+        let mut state = State::get();
+        state.profiles.remove_mod(index);
+        state.refresh_content_index();
+        state.save();
+    }
+
+    /// Returns whether there was an edit of the current profile to undo
+    pub fn undo_profile_edit() -> bool {
+        let mut state = State::get();
+        let undone = state.profiles.undo();
+        if undone {
+            state.refresh_content_index();
+            state.save();
         }
+        undone
+    }
 
-        for mod_state in state.profiles.get_mods() {
-            if !mod_state.enabled { continue }
+    /// Returns whether there was an undone edit of the current profile to redo
+    pub fn redo_profile_edit() -> bool {
+        let mut state = State::get();
+        let redone = state.profiles.redo();
+        if redone {
+            state.refresh_content_index();
+            state.save();
+        }
+        redone
+    }
 
-            let mut path = mods_folder_path.clone();
+    /// Returns the position of the added mod
+    pub fn add_current_mod() -> Option<usize> {
+        let mut state = State::get();
 
 
-            let mut src_path = None;
-            let dir = std::fs::read_dir(&mod_state.path).unwrap();
-            for entry in dir {
-                let Ok(entry) = entry else { continue };
+        let Some(mod_info) = state.selected_new_mod_info.take() else {
+            error!("No mod info cached");
+            return None; // TODO return and handle error
+        };
+        state.profiles.add_mod(&mod_info.unpacked_data, mod_info.meta);
+        state.refresh_content_index();
 
-                if entry.file_name().to_string_lossy().ends_with(".pak") {
-                    src_path = Some(PathBuf::from(&mod_state.path).join(entry.file_name()));
-                    break;
-                }
-            }
-            let src_path = src_path.unwrap();
+        state.save();
+        Some(state.profiles.get_mods().len() - 1)
+    }
 
-            path.push(src_path.file_name().expect("Mod file not a file"));
+    /// Swaps the mod at `index` for the mod currently being added, which is a newer version of it
+    pub fn update_with_current_mod(index: usize) -> Option<usize> {
+        let mut state = State::get();
 
-            if let Err(error) = symlink::symlink_file(&src_path, path) {
-                error!("Could not apply mod '{:?}': {error}", src_path);
-                return;
-            }
+        let Some(mod_info) = state.selected_new_mod_info.take() else {
+            error!("No mod info cached");
+            return None;
+        };
+        if index >= state.profiles.get_mods().len() {
+            error!("Could not find mod to update at position {index}");
+            state.selected_new_mod_info = Some(mod_info);
+            return None;
         }
+        let retention = state.version_retention;
+        state.profiles.update_mod(index, &mod_info.unpacked_data, mod_info.meta, retention);
+        state.refresh_content_index();
 
-        info!("Writing mod settings");
-        let mut mod_settings_path = PathBuf::from(&state.bg3_appdata);
-        mod_settings_path.push("PlayerProfiles/Public/modsettings.lsx");
+        state.save();
+        Some(index)
+    }
 
-        let mod_settings = state.build_mod_settings();
+    /// `version_index` is the position in the mod's previous versions, newest first
+    pub fn rollback_mod(index: usize, version_index: usize) {
+        let mut state = State::get();
+        state.profiles.rollback_mod(index, version_index);
+        state.refresh_content_index();
+        state.save();
+    }
 
-        let mut mod_settings_file = match OpenOptions::new()
-            .write(true).create(true).truncate(true)
-            .open(&mod_settings_path) {
-            Ok(file) => file,
-            Err(error) => {
-                error!("Could not apply mod_settings: {error:?}");
-                return; // TODO return and handle error
-            }
-        };
-        if let Err(error) = mod_settings_file.write_all(mod_settings.as_bytes()) {
-            error!("Could not write mod_settings: {error:?}");
-            return; // TODO return and handle error
+    pub fn get_version_retention() -> usize {
+        State::get().version_retention
+    }
+
+    pub fn set_version_retention(retention: usize) {
+        let mut state = State::get();
+        state.version_retention = retention;
+        state.profiles.prune_versions(retention);
+        state.save();
+    }
+
+    /// Mods of the current profile sharing a UUID or Folder with the mod currently being added
+    pub fn get_new_mod_collisions() -> Vec<ModCollision> {
+        let state = State::get();
+        let Some(meta) = state.selected_new_mod_info.as_ref().and_then(SelectedNewModInfo::meta) else { return Vec::new() };
+        CollisionDetector::find_collisions_with(state.profiles.get_mods(), meta)
+    }
+
+    pub fn set_mod_enabled_state(index: usize, enabled: bool) {
+        let mut state = State::get();
+        state.profiles.set_mod_enabled_state(index, enabled);
+        state.save();
+    }
+
+    pub fn set_mod_notes(index: usize, notes: String) {
+        let mut state = State::get();
+        state.profiles.set_mod_notes(index, notes);
+        state.save();
+    }
+
+    pub fn move_mods(start: usize, count: usize, position: usize) {
+        let mut state = State::get();
+        state.profiles.move_mods(start, count, position);
+        state.save();
+    }
+
+    pub fn sort_mods() -> Result<(), LoadOrderSortError> {
+        info!("Sorting mods by their dependencies and load order rules");
+        let mut state = State::get();
+        let mods = state.profiles.get_mods();
+        let cycle_names = |cycle: Vec<usize>| cycle.into_iter()
+            .map(|index| State::meta_to_mod_details(mods[index].meta.as_ref(), &mods[index].path, true).name)
+            .collect::<Vec<String>>();
+
+        let mut graph = LoadOrderGraph::from_dependencies(mods);
+        if let Err(cycle) = graph.sort() {
+            let mods = cycle_names(cycle);
+            error!("Mod dependencies form a cycle: {}", mods.join(" -> "));
+            return Err(LoadOrderSortError::DependencyCycle { mods });
         }
-        info!("Mod settings applied");
+
+        graph.add_rules(mods, state.profiles.load_order_rules());
+        for (before, after) in Masterlist::load().load_order_constraints(mods) {
+            graph.add_constraint(before, after);
+        }
+        let order = graph.sort().map_err(|cycle| {
+            let mods = cycle_names(cycle);
+            error!("Load order rules contradict each other: {}", mods.join(" -> "));
+            LoadOrderSortError::ContradictoryRules { mods }
+        })?;
+
+        state.profiles.reorder_mods(&order);
+        state.save();
+        Ok(())
     }
 
-    pub fn get_mods() -> Vec<Mod> {
+    pub fn get_masterlist_findings() -> Vec<MasterlistFinding> {
+        Masterlist::load().evaluate(State::get().profiles.get_mods())
+    }
+
+    pub fn import_masterlist(path: &Path) -> Result<(), MasterlistError> {
+        Masterlist::import(path)
+    }
+
+    /// Creates a profile named after the file from the mods in the library, returns the entries that are not in it
+    pub fn import_bg3mm_load_order(path: &Path) -> Result<Vec<MissingMod>, LoadOrderExchangeError> {
+        let order = Bg3ModManagerOrder::read(path)?;
+        let mut state = State::get();
+
+        let name = path.file_stem().map_or_else(|| "Imported Load Order".into(), |stem| stem.to_string_lossy().to_string());
+        let missing = state.add_profile_from_shared_mods(name, order.into_shared_mods());
+
+        info!("Imported load order from {}, {} mods are missing", path.to_string_lossy(), missing.len());
+        Ok(missing)
+    }
+
+    pub fn get_load_order_code() -> String {
         let state = State::get();
+        LoadOrderCode::encode(state.profiles.current_profile_name(), state.profiles.get_mods())
+    }
 
-        state.profiles.get_mods().iter().map(|mod_state| {
-            State::meta_to_mod_details(mod_state.meta.as_ref(), &PathBuf::from(&mod_state.path), mod_state.enabled)
-        }).collect::<Vec<Mod>>()
+    /// Creates a profile from a load order code, returns the mods that are not in the library
+    pub fn import_load_order_code(code: &str) -> Result<Vec<MissingMod>, LoadOrderExchangeError> {
+        let (name, mods) = LoadOrderCode::decode(code)?;
+        let missing = State::get().add_profile_from_shared_mods(name, mods);
+
+        info!("Imported load order code, {} mods are missing", missing.len());
+        Ok(missing)
     }
 
-    pub fn remove_mod(index: usize) {
+    pub fn export_bg3mm_load_order(path: &Path) -> Result<(), LoadOrderExchangeError> {
+        Bg3ModManagerOrder::from_mods(State::get().profiles.get_mods()).write(path)?;
+        info!("Exported load order to {}", path.to_string_lossy());
+        Ok(())
+    }
+
+    /// Writes the current profile and the package of each of its mods into a zip at `path`
+    pub fn export_modpack(path: &Path) -> Result<(), ModpackError> {
+        let state = State::get();
+
+        let mut packages = Vec::new();
+        for mod_state in state.profiles.get_mods() {
+            let mod_name = || State::meta_to_mod_details(mod_state.meta.as_ref(), &mod_state.path, true).name;
+            let Some(pak_path) = State::find_pak_path(&mod_state.path) else {
+                error!("Cannot find the package of {}", mod_name());
+                return Err(ModpackError::CannotReadMod { mod_name: mod_name() });
+            };
+
+            let Some(hash) = state.package_hash(&mod_state.path) else {
+                return Err(ModpackError::CannotReadMod { mod_name: mod_name() });
+            };
+
+            packages.push(ModpackPackage {
+                pak_path,
+                hash,
+                enabled: mod_state.enabled,
+                meta: mod_state.meta.clone(),
+            });
+        }
+
+        Modpack::write(path, state.profiles.current_profile_name(), &packages)?;
+        info!("Exported modpack to {}", path.to_string_lossy());
+        Ok(())
+    }
+
+    /// Recreates the profile of a modpack, packages already in the mod store are not stored again.
+    /// Nothing is kept when a package cannot be stored or does not match its hash, packages stored before are trashed
+    pub fn import_modpack(path: &Path) -> Result<(), ModpackError> {
+        let mut modpack = Modpack::open(path)?;
         let mut state = State::get();
-        state.profiles.remove_mod(index);
+
+        let previous_profile = state.profiles.current_profile();
+        state.profiles.add_profile(modpack.manifest.profile_name.clone());
+        let imported_profile = state.profiles.current_profile();
+
+        let mut stored_packages = Vec::new();
+        if let Err(error) = state.add_modpack_mods(&mut modpack, path, &mut stored_packages) {
+            // Packages that were in the store before the import stay in the library
+            state.profiles.delete_profile_keeping_data(imported_profile);
+            for data_dir in stored_packages {
+                if let Err(error) = state.profiles.delete_stored_mod(&data_dir) {
+                    error!("Could not remove mod data dir {}: {error}", data_dir.to_string_lossy());
+                }
+            }
+            state.profiles.switch_profile(previous_profile);
+            return Err(error);
+        }
+
+        info!("Imported modpack {}", path.to_string_lossy());
+        state.refresh_content_index();
         state.save();
+        Ok(())
     }
 
-    pub fn add_current_mod() {
+    /// Creates a profile from the manifest at `path`, returns the mods that are neither in the library nor have a source
+    pub fn build_profile_from_manifest(path: &Path) -> Result<Vec<MissingMod>, ProfileManifestError> {
+        let manifest = ProfileManifest::read(path)?;
         let mut state = State::get();
 
+        state.profiles.add_profile(manifest.name.clone());
+        state.profiles.set_manifest_path(path);
+        let library = state.library_mods();
+        let manifest_dir = path.parent().unwrap_or(Path::new(""));
+
+        let mut missing = Vec::new();
+        for manifest_mod in &manifest.mods {
+            let found = state.find_manifest_mod(manifest_mod, &library, manifest_dir);
+            let uuid = found.as_ref().and_then(|(_, meta)| meta.as_ref()).map(|meta| meta.uuid().value().to_string())
+                .or_else(|| manifest_mod.uuid.clone());
+
+            // Rules are kept for mods that are missing, like for removed mods
+            let rules = manifest_mod.rules();
+            if let (Some(uuid), false) = (uuid.as_ref(), rules.is_empty()) {
+                state.profiles.set_load_order_rules(uuid.to_lowercase(), rules);
+            }
 
-        let Some(mod_info) = state.selected_new_mod_info.take() else {
-            error!("No mod info cached");
-            return; // TODO return and handle error
+            let Some((mod_path, meta)) = found else {
+                let name = [Some(&manifest_mod.name), uuid.as_ref(), manifest_mod.hash.as_ref()].into_iter().flatten()
+                    .find(|name| !name.is_empty()).cloned().unwrap_or_default();
+                missing.push(MissingMod { uuid: uuid.unwrap_or_default(), name });
+                continue;
+            };
+            state.profiles.add_mod(&mod_path, meta);
+            if !manifest_mod.enabled {
+                let index = state.profiles.get_mods().len() - 1;
+                state.profiles.set_mod_enabled_state(index, false);
+            }
+        }
+
+        info!("Built profile from {}, {} mods are missing", path.to_string_lossy(), missing.len());
+        state.refresh_content_index();
+        state.save();
+        Ok(missing)
+    }
+
+    /// Writes the current profile to `path`, or to the manifest it was built from or last written to
+    pub fn write_profile_manifest(path: Option<PathBuf>) -> Result<(), ProfileManifestError> {
+        let mut state = State::get();
+        let Some(path) = path.or_else(|| state.profiles.manifest_path().map(Path::to_path_buf)) else {
+            return Err(ProfileManifestError::NoManifestPath);
         };
-        state.profiles.add_mod(&mod_info.unpacked_data, mod_info.meta);
 
+        // Sources only exist in the manifest
+        let previous_manifest = path.is_file().then(|| ProfileManifest::read(&path).ok()).flatten();
+
+        let mods = state.profiles.get_mods().iter().map(|mod_state| {
+            let uuid = mod_state.meta.as_ref().map(|meta| meta.uuid().value().to_string());
+            let hash = state.package_hash(&mod_state.path);
+            let mut manifest_mod = ManifestMod {
+                source: previous_manifest.as_ref().and_then(|manifest| manifest.find_source(uuid.as_deref(), hash.as_deref())).cloned(),
+                name: State::meta_to_mod_details(mod_state.meta.as_ref(), &mod_state.path, true).name,
+                enabled: mod_state.enabled,
+                load_after: Vec::new(),
+                load_before: Vec::new(),
+                pin: None,
+                uuid,
+                hash,
+            };
+            if let Some(rules) = manifest_mod.uuid.as_ref().and_then(|uuid| state.profiles.load_order_rules().get(&uuid.to_lowercase())) {
+                manifest_mod.set_rules(rules);
+            }
+            manifest_mod
+        }).collect();
+
+        ProfileManifest { name: state.profiles.current_profile_name().to_string(), mods }.write(&path)?;
+        info!("Wrote profile manifest {}", path.to_string_lossy());
+        state.profiles.set_manifest_path(&path);
         state.save();
+        Ok(())
     }
 
-    pub fn set_mod_enabled_state(index: usize, enabled: bool) {
+    pub fn get_load_order_rules(index: usize) -> Vec<LoadOrderRule> {
+        let state = State::get();
+        let Some(uuid) = state.profiles.get_mods().get(index).and_then(|mod_state| mod_state.meta.as_ref()).map(|meta| meta.uuid().value()) else {
+            return Vec::new();
+        };
+        state.profiles.load_order_rules().get(&uuid.to_ascii_lowercase()).cloned().unwrap_or_default()
+    }
+
+    pub fn set_load_order_rules(index: usize, rules: Vec<LoadOrderRule>) {
         let mut state = State::get();
-        state.profiles.set_mod_enabled_state(index, enabled);
+        let Some(uuid) = state.profiles.get_mods().get(index).and_then(|mod_state| mod_state.meta.as_ref()).map(|meta| meta.uuid().value().to_ascii_lowercase()) else {
+            error!("Could not set load order rules, mod at position {index} has no UUID");
+            return;
+        };
+        state.profiles.set_load_order_rules(uuid, rules);
         state.save();
     }
 
+    pub fn get_available_dependencies(index: usize) -> Vec<AvailableDependency> {
+        let state = State::get();
+        let library = state.library_mods();
+        DependencyValidator::find_available(state.profiles.get_mods(), index, &library)
+    }
+
+    /// Enables or adds the available dependencies of the mod at `index` and of the dependencies themselves,
+    /// added mods are placed before the mod needing them. Returns the new position of the mod
+    pub fn add_dependencies(index: usize) -> usize {
+        let mut state = State::get();
+        let Some(dependent_path) = state.profiles.get_mods().get(index).map(|mod_state| mod_state.path.clone()) else {
+            error!("Could not find mod at position {index}");
+            return index;
+        };
+        let library = state.library_mods();
+
+        let mut pending = vec![dependent_path.clone()];
+        while let Some(mod_path) = pending.pop() {
+            loop {
+                let mods = state.profiles.get_mods();
+                let Some(mod_index) = mods.iter().position(|mod_state| mod_state.path == mod_path) else { break };
+                let Some(dependency) = DependencyValidator::find_available(mods, mod_index, &library).into_iter().next() else { break };
+
+                match dependency.source {
+                    DependencySource::Profile { index } => {
+                        info!("Enabling dependency {}", dependency.name);
+                        pending.push(mods[index].path.clone());
+                        state.profiles.set_mod_enabled_state(index, true);
+                    }
+                    DependencySource::Library { mod_path } => {
+                        info!("Adding dependency {} from the library", dependency.name);
+                        let meta = library.iter().find(|(path, _)| *path == mod_path).map(|(_, meta)| meta.clone());
+                        state.profiles.insert_mod(mod_index, &mod_path, meta);
+                        pending.push(mod_path);
+                    }
+                }
+            }
+        }
+
+        state.refresh_content_index();
+        state.save();
+        state.profiles.get_mods().iter().position(|mod_state| mod_state.path == dependent_path).unwrap_or(index)
+    }
+
     pub fn get_mod_details(file_path: PathBuf) -> Result<Mod, ModDetailsError> {
         info!("Fetching mod details");
         let mut state = State::get();
@@ -153,14 +520,16 @@ impl State {
         }
 
         let extension = file_path.extension().map(std::ffi::OsStr::to_string_lossy);
-        let data_path = match extension.as_ref().map(std::convert::AsRef::as_ref) {
-            Some("pak") => state.mov_pak(&file_path),
-            Some("zip") => state.extract_zip(&file_path),
-            _ => {
-                error!("File {file_path:?} does not have a supported extension");
-                return Err(ModDetailsError::FilePathDoesNotLeadToValidFile)
-            }
-        };
+        if !matches!(extension.as_deref(), Some("pak" | "zip")) {
+            error!("File {file_path:?} does not have a supported extension");
+            return Err(ModDetailsError::FilePathDoesNotLeadToValidFile)
+        }
+        let stored = ModStore::store(&file_path).map_err(|error| {
+            error!("Could not store {file_path:?}: {error}");
+            ModDetailsError::CannotUnpackPackageFile
+        })?;
+        let data_path = stored.data_dir;
+        state.profiles.set_mod_source(&data_path, &file_path);
 
         let meta = match State::get_mod_metas(&data_path) {
             Ok(mut meta) => {
@@ -172,19 +541,130 @@ impl State {
 
         let details = State::meta_to_mod_details(meta.as_ref(), &file_path, true);
 
-        state.selected_new_mod_info = Some(SelectedNewModInfo::new(file_path, meta, data_path));
+        state.selected_new_mod_info = Some(SelectedNewModInfo::new(file_path, meta, data_path, stored.created));
         state.save();
 
         info!("Returning mod details {{name: {}, description: {}, version: {}}}", details.name, details.description, details.version);
         Ok(details)
     }
 
+    /// Mods found in the game's Mods folder that can be imported, empty once the import was offered
+    pub fn get_installed_game_mods() -> Vec<InstalledGameMod> {
+        let state = State::get();
+        if state.game_mods_import_offered { return Vec::new() }
+
+        state.find_game_mods().iter().map(|game_mod| InstalledGameMod {
+            name: GameMods::name(game_mod),
+            file_name: game_mod.path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            enabled: game_mod.enabled,
+        }).collect()
+    }
+
+    /// Creates a profile from the mods installed without the manager, keeping their order and whether they are active
+    pub fn import_game_mods(mode: GameModsImportMode) -> Result<(), GameModsImportError> {
+        let mut state = State::get();
+        state.game_mods_import_offered = true;
+
+        let game_mods = state.find_game_mods();
+        if game_mods.is_empty() {
+            state.save();
+            return Ok(());
+        }
+
+        info!("Importing {} mods installed without the manager", game_mods.len());
+        state.profiles.add_profile("Imported".into());
+        let mut result = Ok(());
+        for game_mod in game_mods {
+            let mod_name = GameMods::name(&game_mod);
+            let stored = ModStore::store(&game_mod.path).map(|stored| stored.data_dir).and_then(|data_dir_path| match mode {
+                GameModsImportMode::Move => std::fs::remove_file(&game_mod.path).map(|()| data_dir_path),
+                GameModsImportMode::Copy => {
+                    state.profiles.set_mod_source(&data_dir_path, &game_mod.path);
+                    Ok(data_dir_path)
+                }
+            });
+            let data_dir_path = match stored {
+                Ok(data_dir_path) => data_dir_path,
+                Err(error) => {
+                    error!("Could not store installed mod {mod_name}: {error}");
+                    result = Err(GameModsImportError::CannotStoreMod { mod_name });
+                    break;
+                }
+            };
+
+            state.profiles.add_mod(&data_dir_path, game_mod.meta);
+            if !game_mod.enabled {
+                let index = state.profiles.get_mods().len() - 1;
+                state.profiles.set_mod_enabled_state(index, false);
+            }
+        }
+
+        // Mods stored before a failure are kept in the profile
+        state.refresh_content_index();
+        state.save();
+        result
+    }
+
+    pub fn dismiss_game_mods_import() {
+        let mut state = State::get();
+        state.game_mods_import_offered = true;
+        state.save();
+    }
+
     pub fn create_profile(name: String) {
         let mut state = State::get();
         state.profiles.add_profile(name);
         state.save();
     }
 
+    pub fn rename_profile(index: usize, name: String) {
+        let mut state = State::get();
+        state.profiles.rename_profile(index, name);
+        state.save();
+    }
+
+    pub fn duplicate_profile(index: usize, name: String) {
+        let mut state = State::get();
+        state.profiles.duplicate_profile(index, name);
+        state.save();
+    }
+
+    pub fn delete_profile(index: usize) {
+        let mut state = State::get();
+        state.profiles.delete_profile(index);
+        state.refresh_content_index();
+        state.save();
+    }
+
+    pub fn add_child_profile(parent: usize, name: String) {
+        let mut state = State::get();
+        state.profiles.add_child_profile(parent, name);
+        state.refresh_content_index();
+        state.save();
+    }
+
+    pub fn set_profile_parent(profile: usize, parent: Option<usize>) {
+        let mut state = State::get();
+        state.profiles.set_profile_parent(profile, parent);
+        state.refresh_content_index();
+        state.save();
+    }
+
+    pub fn compare_profiles(a: usize, b: usize) -> Result<ProfileDiff, ProfileDiffError> {
+        State::get().profiles.compare_profiles(a, b)
+    }
+
+    pub fn merge_profiles(from: usize, into: usize, changes: &[MergeChange]) -> Result<(), ProfileDiffError> {
+        let mut state = State::get();
+        let retention = state.version_retention;
+        state.profiles.merge_profiles(from, into, changes, retention)?;
+
+        info!("Merged {} changes from profile {from} into profile {into}", changes.len());
+        state.refresh_content_index();
+        state.save();
+        Ok(())
+    }
+
     pub fn switch_profile(index: usize) {
         let mut state = State::get();
         state.profiles.switch_profile(index);
@@ -197,7 +677,128 @@ impl State {
         models::Profiles {
             current_profile: state.profiles.current_profile(),
             profiles: state.profiles.profiles(),
+            parents: state.profiles.parents(),
+        }
+    }
+
+    pub fn get_guid_conflicts() -> Result<Vec<GuidConflict>, ConflictAnalysisError> {
+        info!("Analysing GUID conflicts");
+        let mods = State::get().enabled_mods_in_load_order();
+        ConflictAnalyser::find_guid_conflicts(&mods)
+    }
+
+    pub fn get_file_conflicts() -> Result<Vec<FileConflict>, ConflictAnalysisError> {
+        info!("Analysing file conflicts");
+        let mut state = State::get();
+        state.refresh_content_index();
+        let mods = state.enabled_mods_in_load_order();
+        ConflictAnalyser::find_file_conflicts(&mods, state.profiles.conflict_resolutions(), &state.content_index)
+    }
+
+    pub fn get_vanilla_overrides(game_data_dir: &Path) -> Result<Vec<VanillaOverrides>, VanillaOverridesError> {
+        info!("Finding vanilla files overridden by mods");
+        let vanilla_index = VanillaIndex::load_or_build(game_data_dir)?;
+
+        let mut state = State::get();
+        state.refresh_content_index();
+        let mods = state.mods_in_load_order(false);
+        vanilla_index.find_overrides(&mods, &state.content_index)
+    }
+
+    pub fn find_mods_providing(file_path: &str) -> Vec<LibraryFile> {
+        let mut state = State::get();
+        state.refresh_content_index();
+        state.content_index.find_providers(file_path)
+    }
+
+    pub fn search_library_files(pattern: &str) -> Vec<LibraryFile> {
+        let mut state = State::get();
+        state.refresh_content_index();
+        state.content_index.search(pattern)
+    }
+
+    /// Every mod in the mod store, including the ones no profile uses
+    pub fn get_library_mods() -> Vec<LibraryMod> {
+        let mut state = State::get();
+        state.refresh_content_index();
+        let current_mods = state.profiles.get_mods().iter().map(|mod_state| mod_state.path.as_path()).collect::<HashSet<&Path>>();
+        // The mod being added is only stored until it is added or another file is selected
+        let pending_mod = state.selected_new_mod_info.as_ref().filter(|mod_info| mod_info.created_data())
+            .map(|mod_info| mod_info.unpacked_data.as_path())
+            .filter(|mod_path| !state.profiles.is_data_in_use(mod_path));
+
+        let mut library = ModStore::data_dirs().filter(|mod_path| pending_mod != Some(mod_path.as_path())).filter_map(|mod_path| {
+            let pak_path = State::find_pak_path(&mod_path)?;
+            let meta = match state.content_index.get(&mod_path) {
+                Some(indexed_mod) => indexed_mod.metas.first().cloned(),
+                None => State::get_mod_metas(&mod_path).ok().and_then(|mut metas| metas.drain(..).next()),
+            };
+            let details = State::meta_to_mod_details(meta.as_ref(), &pak_path, false);
+
+            Some(LibraryMod {
+                name: details.name,
+                description: details.description,
+                version: details.version,
+                size: std::fs::metadata(&pak_path).map(|metadata| metadata.len()).unwrap_or_default(),
+                source: state.profiles.mod_source(&mod_path).map(Path::to_path_buf),
+                profiles: state.profiles.profiles_using(&mod_path),
+                retained_by: state.profiles.profiles_retaining(&mod_path),
+                in_use: state.profiles.is_data_in_use(&mod_path),
+                in_current_profile: current_mods.contains(mod_path.as_path()),
+                path: mod_path,
+            })
+        }).collect::<Vec<LibraryMod>>();
+
+        library.sort_by_key(|library_mod| library_mod.name.to_lowercase());
+        library
+    }
+
+    /// Adds a stored mod to the end of the current profile, returns its position
+    pub fn add_library_mod(path: &Path) -> Result<usize, LibraryError> {
+        let mut state = State::get();
+        if !State::is_library_mod(path) {
+            error!("{} is not a mod in the library", path.to_string_lossy());
+            return Err(LibraryError::NotInLibrary);
+        }
+        if let Some(index) = state.profiles.get_mods().iter().position(|mod_state| mod_state.path == path) {
+            info!("{} is already in the profile", path.to_string_lossy());
+            return Ok(index);
         }
+
+        let meta = State::get_mod_metas(path).ok().and_then(|mut metas| metas.drain(..).next());
+        state.profiles.add_mod(path, meta);
+        state.refresh_content_index();
+        state.save();
+        Ok(state.profiles.get_mods().len() - 1)
+    }
+
+    /// Deletes a stored mod, mods used or retained by a profile are kept
+    pub fn delete_library_mod(path: &Path) -> Result<(), LibraryError> {
+        let mut state = State::get();
+        if !State::is_library_mod(path) {
+            error!("{} is not a mod in the library", path.to_string_lossy());
+            return Err(LibraryError::NotInLibrary);
+        }
+        if state.profiles.is_data_in_use(path) {
+            error!("Cannot delete {}, a profile uses it", path.to_string_lossy());
+            return Err(LibraryError::ModInUse);
+        }
+
+        if state.selected_new_mod_info.as_ref().is_some_and(|mod_info| mod_info.unpacked_data == path) {
+            state.selected_new_mod_info = None;
+        }
+        let result = state.profiles.delete_stored_mod(path).map_err(|error| {
+            error!("Could not delete {}: {error}", path.to_string_lossy());
+            LibraryError::CannotDeleteMod
+        });
+        state.save();
+        result
+    }
+
+    pub fn set_conflict_resolution(file_path: &str, mod_index: Option<usize>) {
+        let mut state = State::get();
+        state.profiles.set_conflict_resolution(file_path, mod_index);
+        state.save();
     }
 
 
@@ -207,7 +808,10 @@ impl State {
             selected_new_mod_info: None,
             profiles: Profiles::new(),
             gustav_dev_mod_meta: None,
+            version_retention: State::default_version_retention(),
+            game_mods_import_offered: false,
             bg3_appdata: String::new(),
+            content_index: ContentIndex::default(),
         }
     }
 
@@ -254,6 +858,8 @@ impl State {
         self.profiles = state_data.profiles;
         self.gustav_dev_mod_meta = state_data.gustav_dev_mod_meta;
         self.selected_new_mod_info = state_data.selected_new_mod_info;
+        self.version_retention = state_data.version_retention;
+        self.game_mods_import_offered = state_data.game_mods_import_offered;
         info!("State loaded successfully");
     }
 
@@ -283,10 +889,94 @@ impl State {
         info!("Saved successfully");
     }
 
+    const fn default_version_retention() -> usize {
+        3
+    }
+
     fn get() -> MutexGuard<'static, State> {
         STATE.lock()
     }
 
+    fn apply_mods(&mut self) {
+        info!("Creating symlinks to mod pak files");
+        let mut mods_folder_path = PathBuf::from(&self.bg3_appdata);
+        mods_folder_path.push("Mods/");
+
+        for entry in std::fs::read_dir(&mods_folder_path).unwrap() {
+            if entry.is_err() { continue }
+            let entry = entry.unwrap();
+
+            if let Ok(file_type) = entry.file_type() {
+                if file_type.is_symlink() {
+                    symlink::remove_symlink_auto(entry.path()).unwrap();
+                }
+            }
+        }
+
+        for mod_state in self.profiles.get_mods() {
+            if !mod_state.enabled { continue }
+
+            let mut path = mods_folder_path.clone();
+
+
+            let mut src_path = None;
+            let dir = std::fs::read_dir(&mod_state.path).unwrap();
+            for entry in dir {
+                let Ok(entry) = entry else { continue };
+
+                if entry.file_name().to_string_lossy().ends_with(".pak") {
+                    src_path = Some(PathBuf::from(&mod_state.path).join(entry.file_name()));
+                    break;
+                }
+            }
+            let src_path = src_path.unwrap();
+
+            path.push(src_path.file_name().expect("Mod file not a file"));
+
+            // Mods imported as copies are still installed in the Mods folder
+            if path.is_file() && !path.is_symlink() {
+                warn!("Not linking '{:?}', a pak with the same name is already installed", src_path);
+                continue;
+            }
+
+            if let Err(error) = symlink::symlink_file(&src_path, path) {
+                error!("Could not apply mod '{:?}': {error}", src_path);
+                return;
+            }
+        }
+
+        let enabled_mods = self.enabled_mods_in_load_order();
+        if let Some(resolution_path) = ResolutionPackage::build(&enabled_mods, self.profiles.conflict_resolutions()) {
+            info!("Linking conflict resolution package");
+            let path = mods_folder_path.join(ResolutionPackage::FILE_NAME);
+            if let Err(error) = symlink::symlink_file(&resolution_path, path) {
+                error!("Could not apply conflict resolutions: {error}");
+                return;
+            }
+        }
+
+        info!("Writing mod settings");
+        let mut mod_settings_path = PathBuf::from(&self.bg3_appdata);
+        mod_settings_path.push("PlayerProfiles/Public/modsettings.lsx");
+
+        let mod_settings = self.build_mod_settings();
+
+        let mut mod_settings_file = match OpenOptions::new()
+            .write(true).create(true).truncate(true)
+            .open(&mod_settings_path) {
+            Ok(file) => file,
+            Err(error) => {
+                error!("Could not apply mod_settings: {error:?}");
+                return; // TODO return and handle error
+            }
+        };
+        if let Err(error) = mod_settings_file.write_all(mod_settings.as_bytes()) {
+            error!("Could not write mod_settings: {error:?}");
+            return; // TODO return and handle error
+        }
+        info!("Mod settings applied");
+    }
+
     fn build_mod_settings(&mut self) -> String {
         let gustav_dev_meta = if let Some(gustav_dev_meta) = self.gustav_dev_mod_meta.as_ref() { gustav_dev_meta } else {
             self.gustav_dev_mod_meta = Some(Meta::gustav_dev());
@@ -323,16 +1013,11 @@ impl State {
     fn clear_mod_addition_cache(&mut self) {
         let Some(mod_info) = self.selected_new_mod_info.take() else { return };
 
-        let Some(file_name) = mod_info.src_path().file_stem() else { return };
-
-        let mut mod_store = PathHelper::get_mod_store_dir();
-        mod_store.push(file_name);
+        // Packages that were already stored stay in the library
+        if !mod_info.created_data() { return }
+        let mod_store = mod_info.unpacked_data;
 
-        let cache_is_duplicate = self.profiles.get_mods().iter().any(|state| {
-            state.path == mod_store
-        });
-
-        if !cache_is_duplicate && remove_dir_all(&mod_store).is_err() {
+        if !self.profiles.is_data_in_use(&mod_store) && remove_dir_all(&mod_store).is_err() {
             warn!("Could not remove mod data dir for caches data {}", mod_store.to_string_lossy());
         }
     }
@@ -342,7 +1027,10 @@ impl State {
             name: meta.name().value().to_string(),
             description: meta.description().to_string(),
             version: meta.version().to_string(),
+            uuid: Some(meta.uuid().value().to_string()),
             enabled,
+            notes: String::new(),
+            previous_versions: Vec::new(),
         } } else {
             let name = file_path.file_name().unwrap()
                 .to_string_lossy()
@@ -352,33 +1040,14 @@ impl State {
                 name,
                 description: String::new(),
                 version: String::new(),
+                uuid: None,
                 enabled,
+                notes: String::new(),
+                previous_versions: Vec::new(),
             }
         }
     }
 
-    fn mov_pak(&self, file_path: &Path) -> PathBuf {
-        let data_dir_path = self.profiles.calculate_extraction_path(file_path);
-
-        create_dir_all(&data_dir_path).unwrap();
-
-        copy(file_path, data_dir_path.join(file_path.file_name().unwrap())).unwrap();
-
-        data_dir_path
-    }
-
-    fn extract_zip(&self, file_path: &Path) -> PathBuf {
-        let data_dir_path = self.profiles.calculate_extraction_path(file_path);
-
-        create_dir_all(&data_dir_path).unwrap();
-
-        let src = File::open(file_path).unwrap();
-        trace!("Unzipping file");
-        zip_extract::extract(src, &data_dir_path, true).unwrap();
-        trace!("File Unzipped");
-        data_dir_path
-    }
-
     fn get_mod_metas(dir_path: &Path) -> Result<Vec<Meta>, ModDetailsError> {
         let file_path = State::find_pak_path(dir_path).ok_or_else(|| {
             error!("Cannot find package file");
@@ -396,6 +1065,188 @@ impl State {
         })
     }
 
+    /// Only data dirs in the mod store are handled by the library, paths come from the frontend
+    fn is_library_mod(path: &Path) -> bool {
+        State::find_pak_path(path).is_some() && ModStore::data_dirs().any(|mod_path| mod_path == path)
+    }
+
+    /// Every stored mod with a meta that is not part of the current profile
+    fn library_mods(&self) -> Vec<(PathBuf, Meta)> {
+        let current_mods = self.profiles.get_mods().iter().map(|mod_state| mod_state.path.as_path()).collect::<HashSet<&Path>>();
+        let mut library = Vec::new();
+        let mut seen = HashSet::new();
+
+        for mod_state in self.profiles.all_mods() {
+            let Some(meta) = mod_state.meta.as_ref() else { continue };
+            if current_mods.contains(mod_state.path.as_path()) || !seen.insert(mod_state.path.clone()) { continue }
+            library.push((mod_state.path.clone(), meta.clone()));
+        }
+
+        // Retained versions are only offered through a rollback
+        for mod_state in self.profiles.all_mods() {
+            seen.extend(mod_state.previous_versions.iter().map(|version| version.path.clone()));
+        }
+
+        // Stored mods no profile uses
+        let pending_mod = self.selected_new_mod_info.as_ref().filter(|mod_info| mod_info.created_data())
+            .map(|mod_info| mod_info.unpacked_data.as_path());
+        for mod_path in ModStore::data_dirs() {
+            if seen.contains(&mod_path) || current_mods.contains(mod_path.as_path()) || pending_mod == Some(mod_path.as_path()) { continue }
+
+            let Ok(mut metas) = State::get_mod_metas(&mod_path) else { continue };
+            let Some(meta) = metas.drain(..).next() else { continue };
+            seen.insert(mod_path.clone());
+            library.push((mod_path, meta));
+        }
+
+        library
+    }
+
+    /// Library mods with the listed version are preferred, any other version of them is used otherwise
+    fn add_profile_from_shared_mods(&mut self, name: String, mods: Vec<SharedMod>) -> Vec<MissingMod> {
+        self.profiles.add_profile(name);
+        let library = self.library_mods();
+
+        let mut missing = Vec::new();
+        let mut added = HashSet::new();
+        for shared_mod in mods {
+            if !added.insert(shared_mod.uuid.to_lowercase()) { continue }
+
+            let mut versions = library.iter().filter(|(_, meta)| meta.uuid().value().eq_ignore_ascii_case(&shared_mod.uuid)).peekable();
+            let first_version = versions.peek().copied();
+            let Some((mod_path, meta)) = versions.find(|(_, meta)| meta.version().version64() == shared_mod.version64).or(first_version) else {
+                missing.push(MissingMod { uuid: shared_mod.uuid, name: shared_mod.name });
+                continue;
+            };
+
+            self.profiles.add_mod(mod_path, Some(meta.clone()));
+            if !shared_mod.enabled {
+                let index = self.profiles.get_mods().len() - 1;
+                self.profiles.set_mod_enabled_state(index, false);
+            }
+        }
+
+        self.refresh_content_index();
+        self.save();
+        missing
+    }
+
+    /// Data dirs of the packages it stores are added to `stored_packages`
+    fn add_modpack_mods(&mut self, modpack: &mut Modpack, path: &Path, stored_packages: &mut Vec<PathBuf>) -> Result<(), ModpackError> {
+        for index in 0..modpack.manifest.mods.len() {
+            let entry = &modpack.manifest.mods[index];
+            let enabled = entry.enabled;
+
+            let Some(mod_path) = ModStore::data_dir(&entry.hash) else {
+                error!("Manifest of modpack {} lists an invalid hash for {}", path.to_string_lossy(), entry.mod_name());
+                return Err(ModpackError::InvalidModpack(format!("Invalid hash for {}", entry.mod_name())));
+            };
+            if State::find_pak_path(&mod_path).is_some() {
+                debug!("Package {} is already stored", entry.hash);
+            } else {
+                modpack.extract_package(index, &mod_path)?;
+                self.profiles.set_mod_source(&mod_path, path);
+                stored_packages.push(mod_path.clone());
+            }
+
+            // The meta in the manifest is only used for messages, the package itself describes the mod
+            let meta = State::get_mod_metas(&mod_path).ok().and_then(|mut metas| metas.drain(..).next());
+            self.profiles.add_mod(&mod_path, meta);
+            if !enabled {
+                let index = self.profiles.get_mods().len() - 1;
+                self.profiles.set_mod_enabled_state(index, false);
+            }
+        }
+        Ok(())
+    }
+
+    /// A manifest hash only matches that exact package, otherwise the first stored mod with the UUID is used
+    fn find_manifest_mod(&mut self, manifest_mod: &ManifestMod, library: &[(PathBuf, Meta)], manifest_dir: &Path) -> Option<(PathBuf, Option<Meta>)> {
+        let stored = match (manifest_mod.hash.as_ref(), manifest_mod.uuid.as_ref()) {
+            (Some(hash), _) => ModStore::data_dir(hash)
+                .filter(|mod_path| State::find_pak_path(mod_path).is_some())
+                .map(|mod_path| {
+                    let meta = State::get_mod_metas(&mod_path).ok().and_then(|mut metas| metas.drain(..).next());
+                    (mod_path, meta)
+                }),
+            (None, Some(uuid)) => library.iter()
+                .find(|(_, meta)| meta.uuid().value().eq_ignore_ascii_case(uuid))
+                .map(|(mod_path, meta)| (mod_path.clone(), Some(meta.clone()))),
+            (None, None) => None,
+        };
+        if stored.is_some() { return stored }
+
+        let source = manifest_dir.join(manifest_mod.source.as_ref()?);
+        let extension = source.extension().map(std::ffi::OsStr::to_string_lossy);
+        if !source.is_file() || !matches!(extension.as_deref(), Some("pak" | "zip")) {
+            warn!("Source {} is not a pak or zip file", source.to_string_lossy());
+            return None;
+        }
+
+        info!("Installing {} from the manifest", source.to_string_lossy());
+        let stored = ModStore::store(&source).map_err(|error| {
+            error!("Could not store {}: {error}", source.to_string_lossy());
+        }).ok()?;
+        let data_dir_path = stored.data_dir;
+        if manifest_mod.hash.as_ref().is_some_and(|hash| ModStore::data_dir(hash).as_ref() != Some(&data_dir_path)) {
+            error!("Source {} does not match the hash in the manifest", source.to_string_lossy());
+            // A package that was already stored stays in the library
+            if stored.created && remove_dir_all(&data_dir_path).is_err() {
+                error!("Could not remove mod data dir {}", data_dir_path.to_string_lossy());
+            }
+            return None;
+        }
+        self.profiles.set_mod_source(&data_dir_path, &source);
+
+        let meta = State::get_mod_metas(&data_dir_path).ok().and_then(|mut metas| metas.drain(..).next());
+        Some((data_dir_path, meta))
+    }
+
+    /// SHA-256 of the package in a mod data dir, taken from the content index when it is up to date
+    fn package_hash(&self, mod_path: &Path) -> Option<String> {
+        let pak_path = State::find_pak_path(mod_path)?;
+        match self.content_index.get(mod_path) {
+            Some(indexed_mod) if indexed_mod.pak_path == pak_path => Some(indexed_mod.hash.clone()),
+            _ => HashHelper::hash_file(&pak_path).map_err(|error| {
+                error!("Cannot hash package {}: {error}", pak_path.to_string_lossy());
+            }).ok(),
+        }
+    }
+
+    fn find_game_mods(&self) -> Vec<GameMod> {
+        let bg3_appdata = PathBuf::from(&self.bg3_appdata);
+        GameMods::find(&bg3_appdata.join("Mods"), &bg3_appdata.join("PlayerProfiles/Public/modsettings.lsx"))
+    }
+
+    fn refresh_content_index(&mut self) {
+        let State { profiles, content_index, .. } = self;
+        content_index.refresh(profiles.all_mods().map(|mod_state| mod_state.path.as_path()));
+    }
+
+    /// Metas stored with a profile are only read when the mod is added, take newer reads from the content index
+    fn refresh_metas(&mut self) {
+        let State { profiles, content_index, .. } = self;
+        for mod_state in profiles.all_mods_mut() {
+            let Some(meta) = content_index.get(&mod_state.path).and_then(|indexed_mod| indexed_mod.metas.first()) else { continue };
+            mod_state.meta = Some(meta.clone());
+        }
+    }
+
+    fn enabled_mods_in_load_order(&self) -> Vec<AnalysedMod> {
+        self.mods_in_load_order(true)
+    }
+
+    fn mods_in_load_order(&self, enabled_only: bool) -> Vec<AnalysedMod> {
+        self.profiles.get_mods().iter().enumerate()
+            .filter(|(_, mod_state)| mod_state.enabled || !enabled_only)
+            .map(|(index, mod_state)| AnalysedMod {
+                index,
+                name: State::meta_to_mod_details(mod_state.meta.as_ref(), &mod_state.path, true).name,
+                path: mod_state.path.clone(),
+            })
+            .collect()
+    }
+
     fn find_pak_path(dir_path: &Path) -> Option<PathBuf> {
         let dir = std::fs::read_dir(dir_path).ok()?;
         for entry in dir {
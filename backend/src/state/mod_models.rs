@@ -2,11 +2,22 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use package_helper::Meta;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModState {
     pub meta: Option<Meta>,
     pub path: PathBuf,
     pub enabled: bool,
+    #[serde(default)]
+    pub notes: String,
+    /// Versions this mod was updated from that are still in the mod store, newest first
+    #[serde(default)]
+    pub previous_versions: Vec<ModVersion>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModVersion {
+    pub meta: Option<Meta>,
+    pub path: PathBuf,
 }
 
 impl From<SelectedNewModInfo> for ModState {
@@ -15,6 +26,8 @@ impl From<SelectedNewModInfo> for ModState {
             meta: value.meta,
             path: value.unpacked_data.clone(),
             enabled: true,
+            notes: String::new(),
+            previous_versions: Vec::new(),
         }
     }
 }
@@ -24,14 +37,18 @@ pub struct SelectedNewModInfo {
     src_path: PathBuf,
     pub meta: Option<Meta>,
     pub unpacked_data: PathBuf,
+    /// Whether selecting the file stored its package, data dirs that were already stored belong to the library
+    #[serde(default)]
+    created_data: bool,
 }
 
 impl SelectedNewModInfo {
-    pub fn new(src_path: PathBuf, meta: Option<Meta>, unpacked_data: PathBuf) -> SelectedNewModInfo {
+    pub fn new(src_path: PathBuf, meta: Option<Meta>, unpacked_data: PathBuf, created_data: bool) -> SelectedNewModInfo {
         SelectedNewModInfo {
             src_path,
             meta,
             unpacked_data,
+            created_data,
         }
     }
 
@@ -42,4 +59,8 @@ impl SelectedNewModInfo {
     pub fn meta(&self) -> Option<&Meta> {
         self.meta.as_ref()
     }
+
+    pub fn created_data(&self) -> bool {
+        self.created_data
+    }
 }
\ No newline at end of file
@@ -0,0 +1,314 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::{error, info, trace, warn};
+use crate::state::helpers::{HashHelper, PathHelper};
+use crate::state::State;
+
+/// The data dir a package was stored in
+pub(crate) struct StoredPackage {
+    pub data_dir: PathBuf,
+    /// False when the package was already in the store, the data dir then belongs to the library
+    pub created: bool,
+}
+
+/// Mod data dirs named after the SHA-256 of their package, a package is stored once however many profiles use it
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct ModStore {
+    #[serde(default)]
+    format: u32,
+    /// How many profile mods and retained versions use each data dir
+    #[serde(default)]
+    references: HashMap<PathBuf, usize>,
+    /// The pak or zip each data dir was stored from
+    #[serde(default)]
+    sources: HashMap<PathBuf, PathBuf>,
+}
+
+impl ModStore {
+    /// Stores before format 1 kept mods in `Mods/<profile name>/<file stem>`
+    const FORMAT: u32 = 1;
+    const STAGING_DIR_NAME: &'static str = ".staging";
+    /// Data dirs deleted from the library or dropped by version retention are kept here while an undo can bring them back
+    const TRASH_DIR_NAME: &'static str = ".trash";
+
+    pub fn new() -> ModStore {
+        ModStore {
+            format: Self::FORMAT,
+            references: HashMap::new(),
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Data dir of the package with this hash, `None` when the value is not a hash and could point outside the store
+    pub fn data_dir(hash: &str) -> Option<PathBuf> {
+        HashHelper::is_hash(hash).then(|| PathHelper::get_mod_store_dir().join(hash))
+    }
+
+    /// Copies a pak or unpacks a zip into the store and returns its data dir
+    pub fn store(file_path: &Path) -> io::Result<StoredPackage> {
+        let staging_dir = PathHelper::get_mod_store_dir().join(Self::STAGING_DIR_NAME);
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir)?;
+        }
+        fs::create_dir_all(&staging_dir)?;
+
+        match file_path.extension().and_then(std::ffi::OsStr::to_str) {
+            Some("pak") => {
+                fs::copy(file_path, staging_dir.join(file_path.file_name().unwrap()))?;
+            }
+            Some("zip") => {
+                trace!("Unzipping file");
+                zip_extract::extract(File::open(file_path)?, &staging_dir, true)
+                    .map_err(|error| io::Error::new(ErrorKind::InvalidData, error.to_string()))?;
+                trace!("File Unzipped");
+            }
+            _ => return Err(io::Error::new(ErrorKind::InvalidInput, "Only pak and zip files can be stored")),
+        }
+
+        Self::commit(&staging_dir)
+    }
+
+    /// Moves `dir` to the data dir named after the hash of its package, or drops it when that package is already stored
+    pub fn commit(dir: &Path) -> io::Result<StoredPackage> {
+        let pak_path = State::find_pak_path(dir)
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "No package found"))?;
+        let data_dir = Self::data_dir(&HashHelper::hash_file(&pak_path)?)
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "Package hash is invalid"))?;
+
+        if data_dir == dir {
+            return Ok(StoredPackage { data_dir, created: false });
+        }
+        if State::find_pak_path(&data_dir).is_some() {
+            info!("Package is already stored in {}", data_dir.to_string_lossy());
+            fs::remove_dir_all(dir)?;
+            return Ok(StoredPackage { data_dir, created: false });
+        }
+
+        if data_dir.exists() {
+            fs::remove_dir_all(&data_dir)?;
+        }
+        fs::rename(dir, &data_dir)?;
+        Ok(StoredPackage { data_dir, created: true })
+    }
+
+    /// Data dirs in the store, including ones no profile uses
+    pub fn data_dirs() -> impl Iterator<Item = PathBuf> {
+        fs::read_dir(PathHelper::get_mod_store_dir()).into_iter().flatten().flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir() && path.file_name().is_some_and(|name| name != Self::STAGING_DIR_NAME && name != Self::TRASH_DIR_NAME))
+    }
+
+    pub fn is_referenced(&self, path: &Path) -> bool {
+        self.references.get(path).is_some_and(|references| *references > 0)
+    }
+
+    /// Brings the data dir back from the trash when it was moved there
+    pub fn add_reference(&mut self, path: &Path) {
+        if !path.exists() {
+            if let Some(trash_path) = Self::trash_path(path).filter(|trash_path| trash_path.exists()) {
+                info!("Restoring mod data dir {} from the trash", path.to_string_lossy());
+                if let Err(error) = fs::rename(&trash_path, path) {
+                    error!("Could not restore mod data dir {}: {error}", path.to_string_lossy());
+                }
+            }
+        }
+        *self.references.entry(path.to_owned()).or_default() += 1;
+    }
+
+    /// Data dirs nothing uses anymore stay in the store and are listed in the library until deleted from there
+    pub fn remove_reference(&mut self, path: &Path) {
+        let Some(references) = self.references.get_mut(path) else {
+            warn!("Removing a reference to unreferenced mod data dir {}", path.to_string_lossy());
+            return;
+        };
+
+        *references -= 1;
+        if *references == 0 {
+            self.references.remove(path);
+        }
+    }
+
+    /// Removes a reference that is dropped for good, like a retained version beyond the retention count or a mod of a
+    /// deleted profile, its data dir is trashed once nothing uses it
+    pub fn discard(&mut self, path: &Path) {
+        self.remove_reference(path);
+        if !self.is_referenced(path) {
+            if let Err(error) = Self::trash(path) {
+                error!("Could not move mod data dir {} to the trash: {error}", path.to_string_lossy());
+            }
+        }
+    }
+
+    /// Deletes trashed data dirs except the ones in `restorable`
+    pub fn empty_trash(&mut self, restorable: &HashSet<&Path>) {
+        let trashed = fs::read_dir(PathHelper::get_mod_store_dir().join(Self::TRASH_DIR_NAME)).into_iter().flatten().flatten();
+        for trash_entry in trashed {
+            let Some(path) = Self::data_dir(&trash_entry.file_name().to_string_lossy()) else {
+                warn!("Skipping {}, it is not a trashed mod data dir", trash_entry.path().to_string_lossy());
+                continue;
+            };
+            // A package stored again after it was trashed makes the trashed copy redundant
+            if restorable.contains(path.as_path()) && !path.exists() { continue }
+
+            trace!("Deleting trashed mod data dir {}", path.to_string_lossy());
+            if fs::remove_dir_all(trash_entry.path()).is_err() {
+                error!("Could not delete trashed mod data dir {}", path.to_string_lossy());
+            }
+            if !path.exists() {
+                self.sources.remove(&path);
+            }
+        }
+    }
+
+    fn trash_path(path: &Path) -> Option<PathBuf> {
+        Some(PathHelper::get_mod_store_dir().join(Self::TRASH_DIR_NAME).join(path.file_name()?))
+    }
+
+    fn trash(path: &Path) -> io::Result<()> {
+        let trash_path = Self::trash_path(path)
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Data dir has no name"))?;
+        if trash_path.exists() {
+            fs::remove_dir_all(&trash_path)?;
+        }
+        fs::create_dir_all(trash_path.parent().unwrap())?;
+        fs::rename(path, trash_path)
+    }
+
+    pub fn source(&self, path: &Path) -> Option<&Path> {
+        self.sources.get(path).map(PathBuf::as_path)
+    }
+
+    pub fn set_source(&mut self, path: &Path, source: &Path) {
+        self.sources.insert(path.to_owned(), source.to_owned());
+    }
+
+    /// Moves a data dir nothing uses to the trash, `empty_trash` deletes it once no undo can bring it back
+    pub fn delete(&mut self, path: &Path) -> io::Result<()> {
+        if self.is_referenced(path) {
+            return Err(io::Error::new(ErrorKind::PermissionDenied, "The mod is in use"));
+        }
+        Self::trash(path)
+    }
+
+    /// Rebuilds the counts from every path in use, one path per reference
+    pub fn recount<'a>(&mut self, paths: impl Iterator<Item = &'a Path>) {
+        self.references.clear();
+        for path in paths {
+            self.add_reference(path);
+        }
+    }
+
+    pub fn needs_migration(&self) -> bool {
+        self.format < Self::FORMAT
+    }
+
+    /// Moves every data dir in `paths` to the hashed layout, returns where each of them ended up. The migration is
+    /// retried on the next start when a data dir could not be moved
+    pub fn migrate<'a>(&mut self, paths: impl Iterator<Item = &'a Path>) -> HashMap<PathBuf, PathBuf> {
+        info!("Migrating the mod store");
+        let mut migrated_paths = HashMap::new();
+        let mut failed = false;
+        for path in paths {
+            if migrated_paths.contains_key(path) { continue }
+
+            match Self::commit(path) {
+                Ok(stored) => {
+                    migrated_paths.insert(path.to_owned(), stored.data_dir);
+                }
+                Err(error) => {
+                    error!("Could not migrate mod data dir {}, it is kept in place: {error}", path.to_string_lossy());
+                    failed = true;
+                }
+            }
+        }
+
+        // Profile dirs of the old layout are left empty unless they held mods no profile used
+        for profile_dir in Self::data_dirs() {
+            if State::find_pak_path(&profile_dir).is_none() && fs::remove_dir(&profile_dir).is_ok() {
+                trace!("Removed empty profile dir {}", profile_dir.to_string_lossy());
+            }
+        }
+
+        if !failed {
+            self.format = Self::FORMAT;
+        }
+        migrated_paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::fs;
+    use std::io::ErrorKind;
+    use std::path::{Path, PathBuf};
+    use crate::state::helpers::PathHelper;
+    use crate::state::test_mods::test_data_dir;
+    use super::ModStore;
+
+    /// A dir in the store holding a package with `content`
+    fn package_dir(name: &str, content: &str) -> PathBuf {
+        let dir = PathHelper::get_mod_store_dir().join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Mod.pak"), content).unwrap();
+        dir
+    }
+
+    #[test]
+    fn commits_each_package_once() {
+        test_data_dir("commits_each_package_once");
+        let stored = ModStore::commit(&package_dir("first", "package")).unwrap();
+        assert!(stored.created);
+        assert!(stored.data_dir.join("Mod.pak").exists());
+        assert!(!PathHelper::get_mod_store_dir().join("first").exists());
+
+        let again = ModStore::commit(&package_dir("second", "package")).unwrap();
+        assert!(!again.created);
+        assert_eq!(again.data_dir, stored.data_dir);
+        assert!(!PathHelper::get_mod_store_dir().join("second").exists());
+    }
+
+    #[test]
+    fn keeps_dirs_without_package_in_place() {
+        test_data_dir("keeps_dirs_without_package_in_place");
+        let dir = PathHelper::get_mod_store_dir().join("broken");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("readme.txt"), "no package").unwrap();
+
+        let error = ModStore::commit(&dir).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert!(dir.join("readme.txt").exists());
+    }
+
+    #[test]
+    fn empties_trash_except_restorable_dirs() {
+        test_data_dir("empties_trash_except_restorable_dirs");
+        let kept = ModStore::commit(&package_dir("kept", "kept")).unwrap().data_dir;
+        let dropped = ModStore::commit(&package_dir("dropped", "dropped")).unwrap().data_dir;
+
+        let mut store = ModStore::new();
+        store.recount([kept.as_path(), kept.as_path(), dropped.as_path()].into_iter());
+        store.discard(&kept);
+        assert!(kept.exists(), "a data dir still in use is not trashed");
+        store.discard(&kept);
+        store.discard(&dropped);
+        assert!(!kept.exists() && !dropped.exists());
+
+        store.empty_trash(&HashSet::from([kept.as_path()]));
+        store.add_reference(&kept);
+        store.add_reference(&dropped);
+        assert!(kept.join("Mod.pak").exists());
+        assert!(!dropped.exists());
+    }
+
+    #[test]
+    fn rejects_data_dirs_outside_the_store() {
+        assert!(ModStore::data_dir("../state").is_none());
+        assert!(ModStore::data_dir(&"A".repeat(64)).is_none());
+        let hash = "0".repeat(64);
+        assert_eq!(ModStore::data_dir(&hash).unwrap().file_name(), Some(Path::new(&hash).as_os_str()));
+    }
+}
@@ -0,0 +1,161 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use zip::write::FileOptions;
+use models::ModpackError;
+use package_helper::Meta;
+use crate::error;
+use crate::state::helpers::HashHelper;
+
+/// Describes a profile shared as a zip, packages are stored once per hash
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ModpackManifest {
+    pub format: u32,
+    pub profile_name: String,
+    /// In load order
+    pub mods: Vec<ModpackEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ModpackEntry {
+    /// Path of the package inside the archive
+    pub file: String,
+    /// SHA-256 of the package
+    pub hash: String,
+    pub enabled: bool,
+    pub meta: Option<Meta>,
+}
+
+impl ModpackEntry {
+    pub fn mod_name(&self) -> String {
+        match self.meta.as_ref() {
+            Some(meta) => meta.name().value().to_string(),
+            None => Path::new(&self.file).file_stem().unwrap_or_default().to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// A mod of the exported profile
+pub(crate) struct ModpackPackage {
+    pub pak_path: PathBuf,
+    pub hash: String,
+    pub enabled: bool,
+    pub meta: Option<Meta>,
+}
+
+pub(crate) struct Modpack {
+    archive: ZipArchive<File>,
+    pub manifest: ModpackManifest,
+}
+
+impl Modpack {
+    const MANIFEST_NAME: &'static str = "manifest.json";
+    const FORMAT: u32 = 1;
+
+    pub fn write(path: &Path, profile_name: &str, packages: &[ModpackPackage]) -> Result<(), ModpackError> {
+        let write_error = |error: &dyn std::fmt::Display| {
+            error!("Could not write modpack {}: {error}", path.to_string_lossy());
+            ModpackError::CannotWriteFile
+        };
+
+        let file = File::create(path).map_err(|error| write_error(&error))?;
+        let mut zip = ZipWriter::new(file);
+        let mut manifest = ModpackManifest {
+            format: Self::FORMAT,
+            profile_name: profile_name.to_string(),
+            mods: Vec::with_capacity(packages.len()),
+        };
+
+        for package in packages {
+            let file_name = package.pak_path.file_name().unwrap_or_default().to_string_lossy();
+            let archived_path = format!("mods/{}/{file_name}", package.hash);
+
+            if !manifest.mods.iter().any(|entry| entry.file == archived_path) {
+                let mut pak = File::open(&package.pak_path).map_err(|error| {
+                    error!("Could not read package {}: {error}", package.pak_path.to_string_lossy());
+                    ModpackError::CannotReadMod { mod_name: file_name.to_string() }
+                })?;
+                let large_file = pak.metadata().is_ok_and(|metadata| metadata.len() >= u64::from(u32::MAX));
+                let options = FileOptions::default().compression_method(CompressionMethod::Deflated).large_file(large_file);
+
+                zip.start_file(archived_path.as_str(), options).map_err(|error| write_error(&error))?;
+                io::copy(&mut pak, &mut zip).map_err(|error| write_error(&error))?;
+            }
+
+            manifest.mods.push(ModpackEntry {
+                file: archived_path,
+                hash: package.hash.clone(),
+                enabled: package.enabled,
+                meta: package.meta.clone(),
+            });
+        }
+
+        let manifest = serde_json::to_string_pretty(&manifest).map_err(|error| write_error(&error))?;
+        zip.start_file(Self::MANIFEST_NAME, FileOptions::default()).map_err(|error| write_error(&error))?;
+        io::Write::write_all(&mut zip, manifest.as_bytes()).map_err(|error| write_error(&error))?;
+        zip.finish().map_err(|error| write_error(&error))?;
+        Ok(())
+    }
+
+    pub fn open(path: &Path) -> Result<Modpack, ModpackError> {
+        let file = File::open(path).map_err(|error| {
+            error!("Could not open modpack {}: {error}", path.to_string_lossy());
+            ModpackError::CannotReadFile
+        })?;
+        let mut archive = ZipArchive::new(file).map_err(|error| {
+            error!("Modpack {} is not a zip archive: {error}", path.to_string_lossy());
+            ModpackError::InvalidModpack(error.to_string())
+        })?;
+
+        let mut manifest = String::new();
+        archive.by_name(Self::MANIFEST_NAME)
+            .map_err(|error| error.to_string())
+            .and_then(|mut file| file.read_to_string(&mut manifest).map_err(|error| error.to_string()))
+            .map_err(|error| {
+                error!("Could not read the manifest of modpack {}: {error}", path.to_string_lossy());
+                ModpackError::InvalidModpack(error)
+            })?;
+        let manifest = serde_json::from_str::<ModpackManifest>(&manifest).map_err(|error| {
+            error!("Manifest of modpack {} is invalid: {error}", path.to_string_lossy());
+            ModpackError::InvalidModpack(error.to_string())
+        })?;
+
+        Ok(Modpack { archive, manifest })
+    }
+
+    /// Unpacks the package of the manifest entry at `index` into `data_dir_path` and checks it has the listed hash
+    pub fn extract_package(&mut self, index: usize, data_dir_path: &Path) -> Result<(), ModpackError> {
+        let entry = &self.manifest.mods[index];
+        let mod_name = entry.mod_name();
+        let store_error = |error: &dyn std::fmt::Display| {
+            error!("Could not store {mod_name} from the modpack: {error}");
+            ModpackError::CannotStoreMod { mod_name: mod_name.clone() }
+        };
+
+        // Only the file name is used so the manifest cannot place files outside the mod store
+        let file_name = Path::new(&entry.file).file_name().ok_or_else(|| store_error(&"the manifest lists no package"))?;
+        let pak_path = data_dir_path.join(file_name);
+
+        let mut packaged_file = self.archive.by_name(&entry.file).map_err(|error| store_error(&error))?;
+        fs::create_dir_all(data_dir_path).map_err(|error| store_error(&error))?;
+        let hash = File::create(&pak_path)
+            .and_then(|mut pak| io::copy(&mut packaged_file, &mut pak))
+            .and_then(|_| HashHelper::hash_file(&pak_path));
+
+        let result = match hash {
+            Ok(hash) if hash == entry.hash => return Ok(()),
+            Ok(_) => {
+                error!("Package of {mod_name} does not match the hash in the manifest");
+                Err(ModpackError::HashMismatch { mod_name: mod_name.clone() })
+            }
+            Err(error) => Err(store_error(&error)),
+        };
+
+        if fs::remove_dir_all(data_dir_path).is_err() {
+            error!("Could not remove mod data dir {}", data_dir_path.to_string_lossy());
+        }
+        result
+    }
+}
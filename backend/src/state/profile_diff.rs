@@ -0,0 +1,177 @@
+use std::collections::{HashMap, HashSet};
+use models::{ComparedMod, EnabledDifference, OrderDifference, ProfileDiff, VersionDifference};
+use crate::state::mod_models::ModState;
+
+pub(crate) struct ProfileComparer;
+
+impl ProfileComparer {
+    /// Lowercase UUID of the mod, or its data dir when it has no meta
+    pub fn mod_id(mod_state: &ModState) -> String {
+        mod_state.meta.as_ref()
+            .map_or_else(|| mod_state.path.to_string_lossy().to_string(), |meta| meta.uuid().value().to_lowercase())
+    }
+
+    /// Position of each mod by id, only the first of mods sharing a UUID is compared
+    pub fn positions(mods: &[ModState]) -> HashMap<String, usize> {
+        let mut positions = HashMap::new();
+        for (index, mod_state) in mods.iter().enumerate() {
+            positions.entry(ProfileComparer::mod_id(mod_state)).or_insert(index);
+        }
+        positions
+    }
+
+    pub fn compare(a: &[ModState], b: &[ModState]) -> ProfileDiff {
+        let positions_a = ProfileComparer::positions(a);
+        let positions_b = ProfileComparer::positions(b);
+        let mut diff = ProfileDiff::default();
+
+        let mut common = Vec::new();
+        for (index_a, mod_a) in a.iter().enumerate() {
+            let id = ProfileComparer::mod_id(mod_a);
+            if positions_a[&id] != index_a { continue }
+
+            let Some(&index_b) = positions_b.get(&id) else {
+                diff.only_in_a.push(ProfileComparer::compared_mod(id, mod_a));
+                continue;
+            };
+            let mod_b = &b[index_b];
+
+            if mod_a.path != mod_b.path {
+                diff.version_differences.push(VersionDifference {
+                    compared_mod: ProfileComparer::compared_mod(id.clone(), mod_a),
+                    version_a: ProfileComparer::version(mod_a),
+                    version_b: ProfileComparer::version(mod_b),
+                });
+            }
+            if mod_a.enabled != mod_b.enabled {
+                diff.enabled_differences.push(EnabledDifference {
+                    compared_mod: ProfileComparer::compared_mod(id.clone(), mod_a),
+                    enabled_a: mod_a.enabled,
+                    enabled_b: mod_b.enabled,
+                });
+            }
+            common.push((id, index_a, index_b));
+        }
+
+        for (index_b, mod_b) in b.iter().enumerate() {
+            let id = ProfileComparer::mod_id(mod_b);
+            if positions_b[&id] == index_b && !positions_a.contains_key(&id) {
+                diff.only_in_b.push(ProfileComparer::compared_mod(id, mod_b));
+            }
+        }
+
+        // Mods outside the longest run that is in the same order in both profiles are the ones that moved
+        common.sort_by_key(|&(_, _, index_b)| index_b);
+        let in_order = ProfileComparer::longest_increasing(&common.iter().map(|&(_, index_a, _)| index_a).collect::<Vec<usize>>());
+        for (id, index_a, index_b) in common {
+            if in_order.contains(&index_a) { continue }
+            diff.order_differences.push(OrderDifference {
+                compared_mod: ProfileComparer::compared_mod(id, &a[index_a]),
+                position_a: index_a,
+                position_b: index_b,
+            });
+        }
+        diff.order_differences.sort_by_key(|difference| difference.position_a);
+
+        diff
+    }
+
+    /// Where a mod that follows the mod at `source_index` in `source` goes in `target`,
+    /// right after the closest mod before it that `target` also has
+    pub fn merge_position(target: &[ModState], source: &[ModState], source_index: usize) -> usize {
+        let target_positions = ProfileComparer::positions(target);
+        source[..source_index].iter().rev()
+            .find_map(|mod_state| target_positions.get(&ProfileComparer::mod_id(mod_state)))
+            .map_or(0, |position| position + 1)
+    }
+
+    fn compared_mod(id: String, mod_state: &ModState) -> ComparedMod {
+        let name = mod_state.meta.as_ref().map_or_else(
+            || mod_state.path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            |meta| meta.name().value().to_string(),
+        );
+        ComparedMod { id, name }
+    }
+
+    fn version(mod_state: &ModState) -> String {
+        mod_state.meta.as_ref().map(|meta| meta.version().to_string()).unwrap_or_default()
+    }
+
+    /// Values of the longest strictly increasing subsequence of `values`
+    fn longest_increasing(values: &[usize]) -> HashSet<usize> {
+        let mut tails: Vec<usize> = Vec::new();
+        let mut previous = vec![None; values.len()];
+        for (index, value) in values.iter().enumerate() {
+            let length = tails.partition_point(|&tail| values[tail] < *value);
+            previous[index] = length.checked_sub(1).map(|before| tails[before]);
+            if length == tails.len() {
+                tails.push(index);
+            } else {
+                tails[length] = index;
+            }
+        }
+
+        let mut longest = HashSet::new();
+        let mut current = tails.last().copied();
+        while let Some(index) = current {
+            longest.insert(values[index]);
+            current = previous[index];
+        }
+        longest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use models::{ComparedMod, EnabledDifference, OrderDifference, VersionDifference};
+    use crate::state::mod_models::ModState;
+    use crate::state::test_mods::test_mod;
+    use super::ProfileComparer;
+
+    fn test_mods(uuids: &[&str]) -> Vec<ModState> {
+        uuids.iter().map(|uuid| test_mod(uuid, &[])).collect()
+    }
+
+    fn compared_mod(uuid: &str) -> ComparedMod {
+        ComparedMod { id: uuid.to_string(), name: uuid.to_string() }
+    }
+
+    fn moved_ids(a: &[&str], b: &[&str]) -> Vec<String> {
+        ProfileComparer::compare(&test_mods(a), &test_mods(b)).order_differences.into_iter()
+            .map(|difference| difference.compared_mod.id)
+            .collect()
+    }
+
+    #[test]
+    fn finds_every_kind_of_difference() {
+        let mut a = test_mods(&["a", "b", "c", "d", "x"]);
+        a[1].path = PathBuf::from("Mods/b-2");
+        a[2].enabled = false;
+        let b = test_mods(&["b", "c", "a", "d", "y"]);
+
+        let diff = ProfileComparer::compare(&a, &b);
+        assert_eq!(diff.only_in_a, [compared_mod("x")]);
+        assert_eq!(diff.only_in_b, [compared_mod("y")]);
+        assert_eq!(diff.version_differences, [VersionDifference { compared_mod: compared_mod("b"), version_a: "1.0.0.0".into(), version_b: "1.0.0.0".into() }]);
+        assert_eq!(diff.enabled_differences, [EnabledDifference { compared_mod: compared_mod("c"), enabled_a: false, enabled_b: true }]);
+        // Moving a alone puts b, c and d in the order of A
+        assert_eq!(diff.order_differences, [OrderDifference { compared_mod: compared_mod("a"), position_a: 0, position_b: 2 }]);
+    }
+
+    #[test]
+    fn moves_the_fewest_mods() {
+        assert!(moved_ids(&["a", "b", "c"], &["a", "b", "c"]).is_empty());
+        assert_eq!(moved_ids(&["a", "b", "c", "d"], &["d", "a", "b", "c"]), ["d"]);
+        assert_eq!(moved_ids(&["a", "b", "c", "d", "e"], &["b", "a", "c", "e", "d"]).len(), 2);
+        assert_eq!(moved_ids(&["a", "b", "c", "d"], &["d", "c", "b", "a"]).len(), 3);
+    }
+
+    #[test]
+    fn compares_mods_without_meta_by_data_dir() {
+        let without_meta = ModState { meta: None, ..test_mod("z", &[]) };
+        let diff = ProfileComparer::compare(&[without_meta], &[]);
+        let id = PathBuf::from("Mods").join("z").to_string_lossy().to_string();
+        assert_eq!(diff.only_in_a, [ComparedMod { id, name: "z".to_string() }]);
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::error;
+use crate::state::mod_models::ModState;
+
+/// A change to the mods of a profile, holding what is needed to revert it
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) enum ProfileEdit {
+    /// Mods added at these positions, in ascending order
+    Added(Vec<(usize, ModState)>),
+    /// Mods removed from these positions, in ascending order
+    Removed(Vec<(usize, ModState)>),
+    /// The states the mods at these positions had before
+    Changed(Vec<(usize, ModState)>),
+    /// The previous position of every mod, in the new order
+    Reordered(Vec<usize>),
+    /// Every mod the profile had before
+    Replaced(Vec<ModState>),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct HistoryEntry {
+    edit: ProfileEdit,
+    /// Conflict resolutions from before the edit, when it changed them
+    conflict_resolutions: Option<HashMap<String, PathBuf>>,
+}
+
+/// Undo and redo journal of the edits to the mods of a profile
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub(crate) struct ProfileHistory {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+impl ProfileHistory {
+    const LENGTH: usize = 50;
+
+    pub fn record(&mut self, edit: ProfileEdit, conflict_resolutions: Option<HashMap<String, PathBuf>>) {
+        self.redo.clear();
+        self.undo.push(HistoryEntry { edit, conflict_resolutions });
+        if self.undo.len() > Self::LENGTH {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Reverts the last edit, returns false when there is nothing to undo
+    pub fn undo(&mut self, mods: &mut Vec<ModState>, conflict_resolutions: &mut HashMap<String, PathBuf>) -> bool {
+        let Some(entry) = self.undo.pop() else { return false };
+        let Some(inverse) = entry.revert(mods, conflict_resolutions) else {
+            self.clear();
+            return false;
+        };
+        self.redo.push(inverse);
+        true
+    }
+
+    /// Makes the last undone edit again, returns false when there is nothing to redo
+    pub fn redo(&mut self, mods: &mut Vec<ModState>, conflict_resolutions: &mut HashMap<String, PathBuf>) -> bool {
+        let Some(entry) = self.redo.pop() else { return false };
+        let Some(inverse) = entry.revert(mods, conflict_resolutions) else {
+            self.clear();
+            return false;
+        };
+        self.undo.push(inverse);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Data dirs of the mods an undo or redo can bring back
+    pub fn data_paths(&self) -> impl Iterator<Item = &Path> {
+        self.undo.iter().chain(self.redo.iter()).flat_map(|entry| {
+            let mods: Box<dyn Iterator<Item = &ModState>> = match &entry.edit {
+                ProfileEdit::Added(mods) | ProfileEdit::Removed(mods) | ProfileEdit::Changed(mods) => Box::new(mods.iter().map(|(_, mod_state)| mod_state)),
+                ProfileEdit::Reordered(_) => Box::new(std::iter::empty()),
+                ProfileEdit::Replaced(mods) => Box::new(mods.iter()),
+            };
+            mods.flat_map(|mod_state| std::iter::once(mod_state.path.as_path())
+                .chain(mod_state.previous_versions.iter().map(|version| version.path.as_path())))
+        })
+    }
+}
+
+impl HistoryEntry {
+    /// Applies the inverse of the edit and returns the entry that makes it again,
+    /// `None` when the edit does not fit the mods anymore and nothing was changed
+    fn revert(self, mods: &mut Vec<ModState>, conflict_resolutions: &mut HashMap<String, PathBuf>) -> Option<HistoryEntry> {
+        let edit = match self.edit {
+            ProfileEdit::Added(added) => {
+                if added.iter().enumerate().any(|(count, (position, _))| *position + added.len() - count > mods.len()) {
+                    error!("Could not undo adding mods, the profile changed");
+                    return None;
+                }
+                let mut removed = added.iter().rev().map(|(position, _)| (*position, mods.remove(*position))).collect::<Vec<(usize, ModState)>>();
+                removed.reverse();
+                ProfileEdit::Removed(removed)
+            }
+            ProfileEdit::Removed(removed) => {
+                if removed.iter().enumerate().any(|(count, (position, _))| *position > mods.len() + count) {
+                    error!("Could not undo removing mods, the profile changed");
+                    return None;
+                }
+                for (position, mod_state) in &removed {
+                    mods.insert(*position, mod_state.clone());
+                }
+                ProfileEdit::Added(removed)
+            }
+            ProfileEdit::Changed(changed) => {
+                if changed.iter().any(|(position, _)| *position >= mods.len()) {
+                    error!("Could not undo changing mods, the profile changed");
+                    return None;
+                }
+                ProfileEdit::Changed(changed.into_iter()
+                    .map(|(position, mod_state)| (position, std::mem::replace(&mut mods[position], mod_state)))
+                    .collect())
+            }
+            ProfileEdit::Reordered(order) => {
+                let mut sorted_order = order.clone();
+                sorted_order.sort_unstable();
+                if !sorted_order.into_iter().eq(0..mods.len()) {
+                    error!("Could not undo reordering mods, the profile changed");
+                    return None;
+                }
+
+                let mut reverted = vec![None; mods.len()];
+                let mut inverse = vec![0; mods.len()];
+                for (position, mod_state) in mods.drain(..).enumerate() {
+                    reverted[order[position]] = Some(mod_state);
+                    inverse[order[position]] = position;
+                }
+                mods.extend(reverted.into_iter().flatten());
+                ProfileEdit::Reordered(inverse)
+            }
+            ProfileEdit::Replaced(previous_mods) => ProfileEdit::Replaced(std::mem::replace(mods, previous_mods)),
+        };
+
+        let conflict_resolutions = self.conflict_resolutions.map(|previous| std::mem::replace(conflict_resolutions, previous));
+        Some(HistoryEntry { edit, conflict_resolutions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use crate::state::mod_models::ModState;
+    use crate::state::test_mods::{test_mod, uuids};
+    use super::{ProfileEdit, ProfileHistory};
+
+    fn test_mods(uuids: &[&str]) -> Vec<ModState> {
+        uuids.iter().map(|uuid| test_mod(uuid, &[])).collect()
+    }
+
+    /// Makes the change to `mods` the way `Profiles` does, records `edit` for it, then undoes and redoes it
+    fn assert_undo_redo(before: &[&str], edit: ProfileEdit, change: impl FnOnce(&mut Vec<ModState>), after: &[&str]) {
+        let mut history = ProfileHistory::default();
+        let mut resolutions = HashMap::new();
+        let mut mods = test_mods(before);
+        change(&mut mods);
+        history.record(edit, None);
+        assert_eq!(uuids(&mods), after);
+
+        assert!(history.undo(&mut mods, &mut resolutions));
+        assert_eq!(uuids(&mods), before);
+        assert!(!history.undo(&mut mods, &mut resolutions));
+
+        assert!(history.redo(&mut mods, &mut resolutions));
+        assert_eq!(uuids(&mods), after);
+        assert!(!history.redo(&mut mods, &mut resolutions));
+    }
+
+    #[test]
+    fn undoes_additions() {
+        let added = vec![(0, test_mod("x", &[])), (3, test_mod("y", &[]))];
+        let change = |mods: &mut Vec<ModState>| {
+            mods.insert(0, test_mod("x", &[]));
+            mods.insert(3, test_mod("y", &[]));
+        };
+        assert_undo_redo(&["a", "b", "c"], ProfileEdit::Added(added), change, &["x", "a", "b", "y", "c"]);
+    }
+
+    #[test]
+    fn undoes_removals() {
+        let removed = vec![(0, test_mod("a", &[])), (2, test_mod("c", &[]))];
+        let change = |mods: &mut Vec<ModState>| {
+            mods.remove(2);
+            mods.remove(0);
+        };
+        assert_undo_redo(&["a", "b", "c", "d"], ProfileEdit::Removed(removed), change, &["b", "d"]);
+    }
+
+    #[test]
+    fn undoes_changes() {
+        let mut history = ProfileHistory::default();
+        let mut resolutions = HashMap::new();
+        let mut mods = test_mods(&["a", "b"]);
+        history.record(ProfileEdit::Changed(vec![(1, mods[1].clone())]), None);
+        mods[1].enabled = false;
+        mods[1].path = PathBuf::from("Mods/b-2");
+
+        assert!(history.undo(&mut mods, &mut resolutions));
+        assert!(mods[1].enabled);
+        assert_eq!(mods[1].path, PathBuf::from("Mods/b"));
+
+        assert!(history.redo(&mut mods, &mut resolutions));
+        assert!(!mods[1].enabled);
+        assert_eq!(mods[1].path, PathBuf::from("Mods/b-2"));
+    }
+
+    #[test]
+    fn undoes_reorders() {
+        // The new order [c, a, b, d] lists the previous position of every mod
+        let change = |mods: &mut Vec<ModState>| {
+            let moved = mods.remove(2);
+            mods.insert(0, moved);
+        };
+        assert_undo_redo(&["a", "b", "c", "d"], ProfileEdit::Reordered(vec![2, 0, 1, 3]), change, &["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn undoes_replacements_and_conflict_resolutions() {
+        let mut history = ProfileHistory::default();
+        let previous_resolutions = HashMap::from([("Public/file.txt".to_string(), PathBuf::from("Mods/a"))]);
+        let mut resolutions = HashMap::from([("Public/file.txt".to_string(), PathBuf::from("Mods/x"))]);
+        let previous_mods = test_mods(&["a", "b"]);
+        let mut mods = test_mods(&["x", "b", "y"]);
+        history.record(ProfileEdit::Replaced(previous_mods), Some(previous_resolutions.clone()));
+
+        assert!(history.undo(&mut mods, &mut resolutions));
+        assert_eq!(uuids(&mods), ["a", "b"]);
+        assert_eq!(resolutions, previous_resolutions);
+
+        assert!(history.redo(&mut mods, &mut resolutions));
+        assert_eq!(uuids(&mods), ["x", "b", "y"]);
+        assert_eq!(resolutions["Public/file.txt"], PathBuf::from("Mods/x"));
+    }
+
+    #[test]
+    fn new_edits_clear_redo() {
+        let mut history = ProfileHistory::default();
+        let mut resolutions = HashMap::new();
+        let mut mods = test_mods(&["a", "b"]);
+        mods.push(test_mod("c", &[]));
+        history.record(ProfileEdit::Added(vec![(2, test_mod("c", &[]))]), None);
+        assert!(history.undo(&mut mods, &mut resolutions));
+
+        let removed = mods.remove(0);
+        history.record(ProfileEdit::Removed(vec![(0, removed)]), None);
+        assert!(!history.redo(&mut mods, &mut resolutions));
+        assert_eq!(uuids(&mods), ["b"]);
+
+        assert!(history.undo(&mut mods, &mut resolutions));
+        assert_eq!(uuids(&mods), ["a", "b"]);
+        assert!(!history.undo(&mut mods, &mut resolutions));
+    }
+
+    #[test]
+    fn stops_at_edits_that_no_longer_fit() {
+        let mut history = ProfileHistory::default();
+        let mut resolutions = HashMap::new();
+        let mut mods = test_mods(&["a", "b", "c"]);
+        history.record(ProfileEdit::Reordered(vec![1, 0, 2]), None);
+        history.record(ProfileEdit::Added(vec![(2, test_mod("c", &[]))]), None);
+        mods.truncate(1);
+
+        assert!(!history.undo(&mut mods, &mut resolutions));
+        assert_eq!(uuids(&mods), ["a"]);
+        assert!(!history.undo(&mut mods, &mut resolutions));
+    }
+}
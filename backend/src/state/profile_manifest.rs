@@ -0,0 +1,120 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use models::{LoadOrderRule, ProfileManifestError};
+use crate::error;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Pin {
+    Top,
+    Bottom,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ManifestMod {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// SHA-256 of the package, picks the exact version when several are stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Only informative
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default = "ManifestMod::default_enabled")]
+    pub enabled: bool,
+    /// Pak or zip installed when the mod is not in the library, relative to the manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub load_after: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub load_before: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<Pin>,
+}
+
+impl ManifestMod {
+    const fn default_enabled() -> bool {
+        true
+    }
+
+    pub fn rules(&self) -> Vec<LoadOrderRule> {
+        let mut rules = Vec::new();
+        rules.extend(self.load_after.iter().map(|uuid| LoadOrderRule::LoadAfter { uuid: uuid.clone() }));
+        rules.extend(self.load_before.iter().map(|uuid| LoadOrderRule::LoadBefore { uuid: uuid.clone() }));
+        match self.pin {
+            Some(Pin::Top) => rules.push(LoadOrderRule::PinTop),
+            Some(Pin::Bottom) => rules.push(LoadOrderRule::PinBottom),
+            None => {}
+        }
+        rules
+    }
+
+    pub fn set_rules(&mut self, rules: &[LoadOrderRule]) {
+        for rule in rules {
+            match rule {
+                LoadOrderRule::LoadAfter { uuid } => self.load_after.push(uuid.clone()),
+                LoadOrderRule::LoadBefore { uuid } => self.load_before.push(uuid.clone()),
+                LoadOrderRule::PinTop => self.pin = Some(Pin::Top),
+                LoadOrderRule::PinBottom => self.pin = Some(Pin::Bottom),
+            }
+        }
+    }
+}
+
+/// A profile written by hand or by the manager so it can be version controlled, mods are listed in load order
+///
+/// ```toml
+/// name = "Honour run"
+///
+/// [[mod]]
+/// uuid = "<uuid>"
+/// hash = "<sha-256 of the pak>"
+/// name = "Some Mod"
+/// enabled = false
+/// source = "archives/SomeMod.zip"
+/// load_after = ["<uuid>"]
+/// pin = "bottom"
+/// ```
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProfileManifest {
+    pub name: String,
+    #[serde(default, rename = "mod")]
+    pub mods: Vec<ManifestMod>,
+}
+
+impl ProfileManifest {
+    pub fn read(path: &Path) -> Result<ProfileManifest, ProfileManifestError> {
+        let manifest = fs::read_to_string(path).map_err(|error| {
+            error!("Could not read profile manifest {}: {error}", path.to_string_lossy());
+            ProfileManifestError::CannotReadFile
+        })?;
+
+        toml::from_str(&manifest).map_err(|error| {
+            error!("Profile manifest {} is invalid: {error}", path.to_string_lossy());
+            ProfileManifestError::InvalidManifest(error.to_string())
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ProfileManifestError> {
+        let manifest = toml::to_string_pretty(self).map_err(|error| {
+            error!("Could not serialize profile manifest: {error}");
+            ProfileManifestError::CannotWriteFile
+        })?;
+
+        fs::write(path, manifest).map_err(|error| {
+            error!("Could not write profile manifest {}: {error}", path.to_string_lossy());
+            ProfileManifestError::CannotWriteFile
+        })
+    }
+
+    /// Sources are kept when the manager writes the manifest, they are looked up by hash and then by UUID
+    pub fn find_source(&self, uuid: Option<&str>, hash: Option<&str>) -> Option<&PathBuf> {
+        let by_hash = self.mods.iter().find(|manifest_mod| hash.is_some() && manifest_mod.hash.as_deref() == hash);
+        let by_uuid = || self.mods.iter().find(|manifest_mod| {
+            uuid.is_some_and(|uuid| manifest_mod.uuid.as_ref().is_some_and(|other| other.eq_ignore_ascii_case(uuid)))
+        });
+        by_hash.or_else(by_uuid)?.source.as_ref()
+    }
+}
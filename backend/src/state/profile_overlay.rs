@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use models::ComparedMod;
+use crate::state::mod_models::ModState;
+use crate::state::profile_diff::ProfileComparer;
+
+/// Local changes of a profile to the mods it inherits from its parent, mods are referenced by their compared id
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub(crate) struct ProfileOverlay {
+    added: HashSet<String>,
+    removed: HashSet<String>,
+    moved: HashSet<String>,
+    /// Inherited mods kept at another version than the parent's
+    versions: HashSet<String>,
+    /// Inherited mods enabled or disabled unlike in the parent
+    enabled: HashSet<String>,
+}
+
+impl ProfileOverlay {
+    /// The changes that turn `parent_mods` into `mods`
+    pub fn derive(parent_mods: &[ModState], mods: &[ModState]) -> ProfileOverlay {
+        let diff = ProfileComparer::compare(mods, parent_mods);
+        let ids = |compared_mods: Vec<ComparedMod>| compared_mods.into_iter().map(|compared_mod| compared_mod.id).collect();
+
+        ProfileOverlay {
+            added: ids(diff.only_in_a),
+            removed: ids(diff.only_in_b),
+            moved: ids(diff.order_differences.into_iter().map(|difference| difference.compared_mod).collect()),
+            versions: ids(diff.version_differences.into_iter().map(|difference| difference.compared_mod).collect()),
+            enabled: ids(diff.enabled_differences.into_iter().map(|difference| difference.compared_mod).collect()),
+        }
+    }
+
+    /// Applies the changes to the current mods of the parent, `mods` is the list of the profile from before the parent changed.
+    /// Added and moved mods go after the mod they followed in that list
+    pub fn resolve(&self, parent_mods: &[ModState], mods: &[ModState]) -> Vec<ModState> {
+        let local_positions = ProfileComparer::positions(mods);
+
+        let mut resolved = Vec::with_capacity(parent_mods.len() + self.added.len());
+        for parent_mod in parent_mods {
+            let id = ProfileComparer::mod_id(parent_mod);
+            if self.removed.contains(&id) { continue }
+
+            // Notes and retained versions are kept per profile
+            let Some(local_mod) = local_positions.get(&id).map(|&index| &mods[index]) else {
+                resolved.push(ModState { notes: String::new(), previous_versions: Vec::new(), ..parent_mod.clone() });
+                continue;
+            };
+            let mut resolved_mod = local_mod.clone();
+            if !self.versions.contains(&id) {
+                resolved_mod.path.clone_from(&parent_mod.path);
+                resolved_mod.meta.clone_from(&parent_mod.meta);
+            }
+            if !self.enabled.contains(&id) {
+                resolved_mod.enabled = parent_mod.enabled;
+            }
+            resolved.push(resolved_mod);
+        }
+
+        for (index, local_mod) in mods.iter().enumerate() {
+            let id = ProfileComparer::mod_id(local_mod);
+            if local_positions[&id] != index { continue }
+
+            let resolved_position = resolved.iter().position(|resolved_mod| ProfileComparer::mod_id(resolved_mod) == id);
+            match resolved_position {
+                Some(position) if self.moved.contains(&id) => {
+                    let moved_mod = resolved.remove(position);
+                    let new_position = ProfileComparer::merge_position(&resolved, mods, index);
+                    resolved.insert(new_position, moved_mod);
+                }
+                // Added mods the parent got as well are inherited from then on
+                None if self.added.contains(&id) => {
+                    let new_position = ProfileComparer::merge_position(&resolved, mods, index);
+                    resolved.insert(new_position, local_mod.clone());
+                }
+                _ => {}
+            }
+        }
+
+        resolved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use crate::state::mod_models::ModState;
+    use crate::state::test_mods::{test_mod, uuids};
+    use super::ProfileOverlay;
+
+    fn test_mods(uuids: &[&str]) -> Vec<ModState> {
+        uuids.iter().map(|uuid| test_mod(uuid, &[])).collect()
+    }
+
+    #[test]
+    fn applies_local_additions_and_removals() {
+        let parent = test_mods(&["a", "b", "c"]);
+        let child = test_mods(&["a", "x", "c"]);
+        let overlay = ProfileOverlay::derive(&parent, &child);
+        assert_eq!(uuids(&overlay.resolve(&parent, &child)), ["a", "x", "c"]);
+
+        // The parent keeps b, so it stays removed in the child
+        let parent = test_mods(&["d", "a", "b", "c"]);
+        assert_eq!(uuids(&overlay.resolve(&parent, &child)), ["d", "a", "x", "c"]);
+    }
+
+    #[test]
+    fn passes_parent_changes_on() {
+        let parent = test_mods(&["a", "b", "c"]);
+        let mut child = parent.clone();
+        child[2].enabled = false;
+        child[1].notes = "Local notes".to_string();
+        let overlay = ProfileOverlay::derive(&parent, &child);
+
+        let mut updated_parent = test_mods(&["b", "a", "c", "d"]);
+        updated_parent[0].path = PathBuf::from("Mods/b-2");
+        let resolved = overlay.resolve(&updated_parent, &child);
+        assert_eq!(uuids(&resolved), ["b", "a", "c", "d"]);
+        assert_eq!(resolved[0].path, PathBuf::from("Mods/b-2"));
+        assert_eq!(resolved[0].notes, "Local notes");
+        assert_eq!(resolved.iter().map(|mod_state| mod_state.enabled).collect::<Vec<bool>>(), [true, true, false, true]);
+    }
+
+    #[test]
+    fn drops_overrides_of_mods_the_parent_removed() {
+        let parent = test_mods(&["a", "b", "c"]);
+        let mut child = test_mods(&["c", "a", "b"]);
+        child[2].path = PathBuf::from("Mods/b-old");
+        child[2].enabled = false;
+        let overlay = ProfileOverlay::derive(&parent, &child);
+        assert_eq!(uuids(&overlay.resolve(&parent, &child)), ["c", "a", "b"]);
+
+        // Overrides only apply to inherited mods, the move of c is kept
+        let parent = test_mods(&["a", "c"]);
+        let resolved = overlay.resolve(&parent, &child);
+        assert_eq!(uuids(&resolved), ["c", "a"]);
+        assert!(resolved.iter().all(|mod_state| mod_state.enabled));
+    }
+}
@@ -1,17 +1,23 @@
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
+use models::{LoadOrderRule, MergeChange, ProfileDiff, ProfileDiffError};
 use package_helper::Meta;
 use crate::error;
-use crate::state::helpers::PathHelper;
-use crate::state::mod_models::ModState;
+use crate::state::helpers::HashHelper;
+use crate::state::mod_models::{ModState, ModVersion};
+use crate::state::mod_store::ModStore;
+use crate::state::profile_diff::ProfileComparer;
+use crate::state::profile_overlay::ProfileOverlay;
+use crate::state::profile_history::{ProfileEdit, ProfileHistory};
 
 #[derive(Serialize, Deserialize)]
 pub struct Profiles {
     current_profile: usize,
     next_profile: usize,
-    profiles: HashMap<usize, Profile>
+    profiles: HashMap<usize, Profile>,
+    #[serde(default)]
+    store: ModStore,
 }
 
 impl Profiles {
@@ -20,27 +26,190 @@ impl Profiles {
             current_profile: 0,
             next_profile: 0,
             profiles: HashMap::new(),
+            store: ModStore::new(),
         }
     }
 
     pub fn init(&mut self) {
-        if !self.profiles.contains_key(&0) {
+        if self.profiles.is_empty() {
             self.add_profile("Default".into());
         }
+
+        if self.store.needs_migration() {
+            self.migrate_store();
+        }
+        // Counts are not trusted across restarts in case state.json was edited
+        let Profiles { profiles, store, .. } = self;
+        store.recount(profiles.values().flat_map(|profile| profile.data_paths()));
+
+        let missing_parents = self.profiles.iter()
+            .filter(|(_, profile)| profile.parent.is_some_and(|parent| !self.profiles.contains_key(&parent)))
+            .map(|(index, _)| *index)
+            .collect::<Vec<usize>>();
+        for profile in missing_parents {
+            error!("Parent of profile {profile} does not exist, it no longer inherits mods");
+            self.profiles.get_mut(&profile).unwrap().parent = None;
+        }
+        self.empty_trash();
     }
 
     pub fn add_mod(&mut self, unpacked_data_path: &Path, meta: Option<Meta>) {
-        self.profiles.get_mut(&self.current_profile).unwrap().add_mod(unpacked_data_path, meta);
+        let previous_resolutions = self.conflict_resolutions().clone();
+        self.store.add_reference(unpacked_data_path);
+        let profile = self.profiles.get_mut(&self.current_profile).unwrap();
+        profile.add_mod(unpacked_data_path, meta);
+
+        let added = (profile.mods.len() - 1, profile.mods[profile.mods.len() - 1].clone());
+        self.finish_edit(self.current_profile, ProfileEdit::Added(vec![added]), previous_resolutions);
+    }
+
+    pub fn insert_mod(&mut self, position: usize, unpacked_data_path: &Path, meta: Option<Meta>) {
+        let previous_resolutions = self.conflict_resolutions().clone();
+        self.store.add_reference(unpacked_data_path);
+        let profile = self.profiles.get_mut(&self.current_profile).unwrap();
+        let position = position.min(profile.mods.len());
+        profile.insert_mod(position, unpacked_data_path, meta);
+
+        let added = (position, profile.mods[position].clone());
+        self.finish_edit(self.current_profile, ProfileEdit::Added(vec![added]), previous_resolutions);
+    }
+
+    /// Swaps the mod at `mod_index` for a new version in the same position, keeping whether it is enabled and its notes.
+    /// Up to `retention` versions it replaced stay in the mod store to roll back to
+    pub fn update_mod(&mut self, mod_index: usize, unpacked_data_path: &Path, meta: Option<Meta>, retention: usize) {
+        let previous_resolutions = self.conflict_resolutions().clone();
+        let Some(previous_mod) = self.get_mods().get(mod_index).cloned() else {
+            error!("Could not find mod to update at position {mod_index}");
+            return;
+        };
+        self.update_profile_mod(self.current_profile, mod_index, unpacked_data_path, meta, retention);
+        self.finish_edit(self.current_profile, ProfileEdit::Changed(vec![(mod_index, previous_mod)]), previous_resolutions);
+    }
+
+    fn update_profile_mod(&mut self, profile: usize, mod_index: usize, unpacked_data_path: &Path, meta: Option<Meta>, retention: usize) {
+        let profile = self.profiles.get_mut(&profile).unwrap();
+        let Some(mod_state) = profile.mods.get_mut(mod_index) else {
+            error!("Could not find mod to update at position {mod_index}");
+            return;
+        };
+
+        // The same package is stored in the same data dir
+        if mod_state.path == unpacked_data_path {
+            mod_state.meta = meta;
+            return;
+        }
+
+        let old_path = std::mem::replace(&mut mod_state.path, unpacked_data_path.to_owned());
+        let old_meta = std::mem::replace(&mut mod_state.meta, meta);
+        mod_state.previous_versions.insert(0, ModVersion { meta: old_meta, path: old_path.clone() });
+        let discarded = mod_state.previous_versions.split_off(retention.min(mod_state.previous_versions.len()));
+        profile.redirect_conflict_resolutions(&old_path, unpacked_data_path);
+
+        self.store.add_reference(unpacked_data_path);
+        for version in discarded {
+            self.store.discard(&version.path);
+        }
+    }
+
+    /// Makes the retained version at `version_index` the current one, the current version is retained in its place
+    pub fn rollback_mod(&mut self, mod_index: usize, version_index: usize) {
+        let previous_resolutions = self.conflict_resolutions().clone();
+        let profile = self.profiles.get_mut(&self.current_profile).unwrap();
+        let Some(mod_state) = profile.mods.get_mut(mod_index) else {
+            error!("Could not find mod to roll back at position {mod_index}");
+            return;
+        };
+        let previous_mod = mod_state.clone();
+        if version_index >= mod_state.previous_versions.len() {
+            error!("Could not find version {version_index} to roll back to");
+            return;
+        }
+
+        let version = mod_state.previous_versions.remove(version_index);
+        let newer_path = std::mem::replace(&mut mod_state.path, version.path.clone());
+        let newer_meta = std::mem::replace(&mut mod_state.meta, version.meta);
+        mod_state.previous_versions.insert(0, ModVersion { meta: newer_meta, path: newer_path.clone() });
+        profile.redirect_conflict_resolutions(&newer_path, &version.path);
+        self.finish_edit(self.current_profile, ProfileEdit::Changed(vec![(mod_index, previous_mod)]), previous_resolutions);
+    }
+
+    /// Drops the oldest retained versions of every mod beyond `retention`
+    pub fn prune_versions(&mut self, retention: usize) {
+        let discarded = self.all_mods_mut()
+            .flat_map(|mod_state| mod_state.previous_versions.split_off(retention.min(mod_state.previous_versions.len())))
+            .collect::<Vec<ModVersion>>();
+
+        for version in discarded {
+            self.store.discard(&version.path);
+        }
+        self.empty_trash();
     }
 
+    /// The data of the removed mod stays in the mod store, so the removal can be undone
     pub fn remove_mod(&mut self, mod_index: usize) {
-        self.profiles.get_mut(&self.current_profile).unwrap().remove_mod(mod_index);
+        let previous_resolutions = self.conflict_resolutions().clone();
+        let Some(removed_mod) = self.get_mods().get(mod_index).cloned() else {
+            error!("Could not find mod to remove at position {mod_index}");
+            return;
+        };
+        self.remove_profile_mod(self.current_profile, mod_index);
+        self.finish_edit(self.current_profile, ProfileEdit::Removed(vec![(mod_index, removed_mod)]), previous_resolutions);
+    }
+
+    /// Reverts the last edit of the current profile, returns false when there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        self.step_history(true)
+    }
+
+    /// Makes the last undone edit of the current profile again, returns false when there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        self.step_history(false)
+    }
+
+    fn step_history(&mut self, undo: bool) -> bool {
+        let Profiles { profiles, store, current_profile, .. } = self;
+        let profile = profiles.get_mut(current_profile).unwrap();
+        let previous_mods = profile.mods.clone();
+        let stepped = if undo {
+            profile.history.undo(&mut profile.mods, &mut profile.conflict_resolutions)
+        } else {
+            profile.history.redo(&mut profile.mods, &mut profile.conflict_resolutions)
+        };
+        if !stepped { return false }
+
+        // Mods brought back are restored from the trash if they were deleted from the library in the meantime
+        for path in profile.data_paths() {
+            store.add_reference(path);
+        }
+        for path in Profile::mod_data_paths(&previous_mods) {
+            store.remove_reference(path);
+        }
+        self.profile_changed(self.current_profile);
+        true
+    }
+
+    fn remove_profile_mod(&mut self, profile: usize, mod_index: usize) {
+        let Some(removed_mod) = self.profiles.get_mut(&profile).unwrap().remove_mod(mod_index) else { return };
+
+        self.store.remove_reference(&removed_mod.path);
+        for version in &removed_mod.previous_versions {
+            self.store.remove_reference(&version.path);
+        }
     }
 
     pub fn get_mods(&self) -> &[ModState] {
         self.profiles.get(&self.current_profile).unwrap().mods.as_slice()
     }
 
+    /// Every mod of every profile, mods shared between profiles are returned once per profile
+    pub fn all_mods(&self) -> impl Iterator<Item = &ModState> {
+        self.profiles.values().flat_map(|profile| profile.mods.iter())
+    }
+
+    pub fn all_mods_mut(&mut self) -> impl Iterator<Item = &mut ModState> {
+        self.profiles.values_mut().flat_map(|profile| profile.mods.iter_mut())
+    }
+
     pub fn profiles(&self) -> HashMap<usize, String> {
         let mut profiles = HashMap::new();
         for (index, profile) in &self.profiles {
@@ -49,16 +218,292 @@ impl Profiles {
         profiles
     }
 
+    pub fn parents(&self) -> HashMap<usize, usize> {
+        self.profiles.iter()
+            .filter_map(|(index, profile)| profile.parent.map(|parent| (*index, parent)))
+            .collect()
+    }
+
     pub fn current_profile(&self) -> usize {
         self.current_profile
     }
 
-    pub fn calculate_extraction_path(&self, src_path: &Path) -> PathBuf {
-        let mut path = PathHelper::get_mod_store_dir();
-        path.push(&self.profiles.get(&self.current_profile).unwrap().name);
-        path.push(src_path.file_stem().unwrap());
+    pub fn current_profile_name(&self) -> &str {
+        &self.profiles.get(&self.current_profile).unwrap().name
+    }
+
+    pub fn needs_store_migration(&self) -> bool {
+        self.store.needs_migration()
+    }
+
+    /// Whether any mod of any profile uses or retains the data dir at `path`
+    pub fn is_data_in_use(&self, path: &Path) -> bool {
+        self.store.is_referenced(path)
+    }
 
-        path
+    /// Names of the profiles with the data dir at `path` in their load order
+    pub fn profiles_using(&self, path: &Path) -> Vec<String> {
+        self.profiles.values()
+            .filter(|profile| profile.mods.iter().any(|mod_state| mod_state.path == path))
+            .map(|profile| profile.name.clone())
+            .collect()
+    }
+
+    /// Names of the profiles keeping the data dir at `path` as a previous version of a mod
+    pub fn profiles_retaining(&self, path: &Path) -> Vec<String> {
+        self.profiles.values()
+            .filter(|profile| profile.mods.iter().flat_map(|mod_state| &mod_state.previous_versions).any(|version| version.path == path))
+            .map(|profile| profile.name.clone())
+            .collect()
+    }
+
+    pub fn mod_source(&self, path: &Path) -> Option<&Path> {
+        self.store.source(path)
+    }
+
+    pub fn set_mod_source(&mut self, path: &Path, source: &Path) {
+        self.store.set_source(path, source);
+    }
+
+    /// Deletes a stored mod no profile uses or retains, it stays in the trash while an undo can bring it back
+    pub fn delete_stored_mod(&mut self, path: &Path) -> std::io::Result<()> {
+        self.store.delete(path)?;
+        self.empty_trash();
+        Ok(())
+    }
+
+    /// Moves the data of every mod to the hashed store layout, mods no profile uses are moved along so they stay in the library
+    fn migrate_store(&mut self) {
+        // Data dirs already moved by an earlier attempt are named after their hash
+        let old_layout_dirs = ModStore::data_dirs()
+            .filter(|dir| dir.file_name().is_some_and(|name| !HashHelper::is_hash(&name.to_string_lossy())))
+            .flat_map(|profile_dir| std::fs::read_dir(profile_dir).into_iter().flatten().flatten())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect::<Vec<PathBuf>>();
+
+        let Profiles { profiles, store, .. } = self;
+        let migrated_paths = store.migrate(profiles.values()
+            .flat_map(|profile| profile.data_paths())
+            .chain(old_layout_dirs.iter().map(PathBuf::as_path)));
+
+        for profile in profiles.values_mut() {
+            for mod_state in &mut profile.mods {
+                let paths = std::iter::once(&mut mod_state.path).chain(mod_state.previous_versions.iter_mut().map(|version| &mut version.path));
+                for path in paths {
+                    if let Some(migrated_path) = migrated_paths.get(path) {
+                        path.clone_from(migrated_path);
+                    }
+                }
+            }
+            for path in profile.conflict_resolutions.values_mut() {
+                if let Some(migrated_path) = migrated_paths.get(path) {
+                    path.clone_from(migrated_path);
+                }
+            }
+        }
+    }
+
+    pub fn compare_profiles(&self, a: usize, b: usize) -> Result<ProfileDiff, ProfileDiffError> {
+        let profile_a = self.profiles.get(&a).ok_or(ProfileDiffError::ProfileNotFound(a))?;
+        let profile_b = self.profiles.get(&b).ok_or(ProfileDiffError::ProfileNotFound(b))?;
+        Ok(ProfileComparer::compare(&profile_a.mods, &profile_b.mods))
+    }
+
+    /// Applies `changes` from the profile `from` to the profile `into` in order, nothing is merged when one of them does not apply
+    pub fn merge_profiles(&mut self, from: usize, into: usize, changes: &[MergeChange], retention: usize) -> Result<(), ProfileDiffError> {
+        let source = self.profiles.get(&from).ok_or(ProfileDiffError::ProfileNotFound(from))?.mods.clone();
+        let target = self.profiles.get(&into).ok_or(ProfileDiffError::ProfileNotFound(into))?;
+        let source_positions = ProfileComparer::positions(&source);
+
+        // Changes are checked against the target as the changes before them leave it
+        let mut target_ids = ProfileComparer::positions(&target.mods).into_keys().collect::<HashSet<String>>();
+        for change in changes {
+            let valid = match change {
+                MergeChange::AddMod { id } => source_positions.contains_key(id) && target_ids.insert(id.clone()),
+                MergeChange::RemoveMod { id } => !source_positions.contains_key(id) && target_ids.remove(id),
+                MergeChange::Version { id } | MergeChange::Enabled { id } | MergeChange::Order { id } =>
+                    source_positions.contains_key(id) && target_ids.contains(id),
+            };
+            if !valid {
+                error!("Could not merge {change:?} from profile {from} into profile {into}");
+                return Err(ProfileDiffError::InvalidChange(format!("{change:?}")));
+            }
+        }
+
+        let previous_resolutions = self.profiles[&into].conflict_resolutions.clone();
+        let previous_mods = self.profiles[&into].mods.clone();
+        for change in changes {
+            let target_position = |profiles: &Profiles, id: &str| ProfileComparer::positions(&profiles.profiles[&into].mods)[id];
+            match change {
+                MergeChange::AddMod { id } => {
+                    let source_mod = &source[source_positions[id]];
+                    let profile = self.profiles.get_mut(&into).unwrap();
+                    let position = ProfileComparer::merge_position(&profile.mods, &source, source_positions[id]);
+                    // Retained versions belong to the source profile
+                    profile.mods.insert(position, ModState { previous_versions: Vec::new(), ..source_mod.clone() });
+                    if let Some(rules) = self.profiles[&from].load_order_rules.get(id).cloned() {
+                        self.profiles.get_mut(&into).unwrap().load_order_rules.entry(id.clone()).or_insert(rules);
+                    }
+                    self.store.add_reference(&source_mod.path);
+                }
+                MergeChange::RemoveMod { id } => {
+                    let position = target_position(self, id);
+                    self.remove_profile_mod(into, position);
+                }
+                MergeChange::Version { id } => {
+                    let source_mod = &source[source_positions[id]];
+                    let position = target_position(self, id);
+                    self.update_profile_mod(into, position, &source_mod.path, source_mod.meta.clone(), retention);
+                }
+                MergeChange::Enabled { id } => {
+                    let position = target_position(self, id);
+                    self.profiles.get_mut(&into).unwrap().set_mod_enabled_state(position, source[source_positions[id]].enabled);
+                }
+                MergeChange::Order { id } => {
+                    let position = target_position(self, id);
+                    let profile = self.profiles.get_mut(&into).unwrap();
+                    let moved_mod = profile.mods.remove(position);
+                    let new_position = ProfileComparer::merge_position(&profile.mods, &source, source_positions[id]);
+                    profile.mods.insert(new_position, moved_mod);
+                }
+            }
+        }
+        self.finish_edit(into, ProfileEdit::Replaced(previous_mods), previous_resolutions);
+        Ok(())
+    }
+
+    /// Creates a profile that inherits the mods of `parent` and switches to it
+    pub fn add_child_profile(&mut self, parent: usize, name: String) {
+        let Some(parent_profile) = self.profiles.get(&parent) else {
+            error!("Could not find profile {parent} to inherit from");
+            return;
+        };
+
+        let mut child = Profile::new(name);
+        child.parent = Some(parent);
+        child.mods = ProfileOverlay::default().resolve(&parent_profile.mods, &[]);
+        for path in child.data_paths() {
+            self.store.add_reference(path);
+        }
+        self.profiles.insert(self.next_profile, child);
+        self.current_profile = self.next_profile;
+        self.next_profile += 1;
+    }
+
+    /// Makes `profile` inherit from `parent` keeping its current mods as local changes, or stops it inheriting
+    pub fn set_profile_parent(&mut self, profile: usize, parent: Option<usize>) {
+        if !self.profiles.contains_key(&profile) {
+            error!("Could not find profile {profile} to set the parent of");
+            return;
+        }
+        if let Some(parent) = parent {
+            if !self.profiles.contains_key(&parent) || self.ancestors(parent).contains(&profile) {
+                error!("Profile {profile} cannot inherit from profile {parent}");
+                return;
+            }
+        }
+
+        self.profiles.get_mut(&profile).unwrap().parent = parent;
+        self.profile_changed(profile);
+    }
+
+    /// Brings the current profile and the profiles it inherits from up to date with their parents
+    pub fn resolve_current_profile(&mut self) {
+        for profile in self.ancestors(self.current_profile).into_iter().rev() {
+            self.resolve_profile(profile);
+        }
+    }
+
+    /// `profile` followed by its parent, the parent of its parent and so on
+    fn ancestors(&self, profile: usize) -> Vec<usize> {
+        let mut ancestors = vec![profile];
+        let mut current = self.profiles.get(&profile).and_then(|profile| profile.parent);
+        while let Some(parent) = current {
+            if ancestors.contains(&parent) {
+                error!("Profile {parent} inherits from itself");
+                break;
+            }
+            ancestors.push(parent);
+            current = self.profiles.get(&parent).and_then(|profile| profile.parent);
+        }
+        ancestors
+    }
+
+    /// Records an edit of `profile` to undo and passes it on to the profiles inheriting from it
+    fn finish_edit(&mut self, profile: usize, edit: ProfileEdit, previous_resolutions: HashMap<String, PathBuf>) {
+        let edited_profile = self.profiles.get_mut(&profile).unwrap();
+        let changed_resolutions = (edited_profile.conflict_resolutions != previous_resolutions).then_some(previous_resolutions);
+        edited_profile.history.record(edit, changed_resolutions);
+        self.profile_changed(profile);
+    }
+
+    /// Deletes trashed mod data no undo or redo of any profile can bring back
+    fn empty_trash(&mut self) {
+        let Profiles { profiles, store, .. } = self;
+        let restorable = profiles.values().flat_map(|profile| profile.history.data_paths()).collect::<HashSet<&Path>>();
+        store.empty_trash(&restorable);
+    }
+
+    /// Records the local changes of `profile` to its parent and passes its mods on to the profiles inheriting from it
+    fn profile_changed(&mut self, profile: usize) {
+        let Profiles { profiles, .. } = self;
+        let overlay = profiles[&profile].parent.map_or_else(ProfileOverlay::default, |parent| {
+            ProfileOverlay::derive(&profiles[&parent].mods, &profiles[&profile].mods)
+        });
+        profiles.get_mut(&profile).unwrap().overlay = overlay;
+
+        let mut changed = vec![profile];
+        let mut resolved = HashSet::from([profile]);
+        while let Some(parent) = changed.pop() {
+            let children = self.profiles.iter()
+                .filter(|(index, child)| child.parent == Some(parent) && !resolved.contains(*index))
+                .map(|(index, _)| *index)
+                .collect::<Vec<usize>>();
+            for child in children {
+                self.resolve_profile(child);
+                resolved.insert(child);
+                changed.push(child);
+            }
+        }
+        self.empty_trash();
+    }
+
+    /// Rebuilds the mods of `profile` from the mods of its parent and its local changes
+    fn resolve_profile(&mut self, profile: usize) {
+        let Profiles { profiles, store, .. } = self;
+        let Some(parent) = profiles[&profile].parent else { return };
+        let resolved = profiles[&profile].overlay.resolve(&profiles[&parent].mods, &profiles[&profile].mods);
+
+        let child = profiles.get_mut(&profile).unwrap();
+        let previous_resolutions = child.conflict_resolutions.clone();
+        let previous_mods = std::mem::replace(&mut child.mods, resolved);
+        for previous_mod in &previous_mods {
+            let id = ProfileComparer::mod_id(previous_mod);
+            let resolved_mod = child.mods.iter().find(|resolved_mod| ProfileComparer::mod_id(resolved_mod) == id);
+            if let Some(resolved_mod) = resolved_mod.filter(|resolved_mod| resolved_mod.path != previous_mod.path) {
+                let resolved_path = resolved_mod.path.clone();
+                child.redirect_conflict_resolutions(&previous_mod.path, &resolved_path);
+            }
+        }
+        let mods = &child.mods;
+        child.conflict_resolutions.retain(|_, path| mods.iter().any(|mod_state| mod_state.path == *path));
+
+        // References of the resolved mods are added first so shared data is not deleted in between
+        for path in child.data_paths() {
+            store.add_reference(path);
+        }
+        for path in Profile::mod_data_paths(&previous_mods) {
+            store.remove_reference(path);
+        }
+
+        // Changes passed on from the parent can be undone in the profile like its own edits
+        let unchanged = previous_mods.len() == child.mods.len() && previous_mods.iter().zip(&child.mods)
+            .all(|(previous_mod, mod_state)| previous_mod.path == mod_state.path && previous_mod.enabled == mod_state.enabled);
+        if !unchanged {
+            let changed_resolutions = (child.conflict_resolutions != previous_resolutions).then_some(previous_resolutions);
+            child.history.record(ProfileEdit::Replaced(previous_mods), changed_resolutions);
+        }
     }
 
     pub fn add_profile(&mut self, name: String) {
@@ -67,6 +512,83 @@ impl Profiles {
         self.next_profile += 1;
     }
 
+    pub fn rename_profile(&mut self, profile: usize, name: String) {
+        let Some(profile) = self.profiles.get_mut(&profile) else {
+            error!("Could not find profile {profile} to rename");
+            return;
+        };
+        profile.name = name;
+    }
+
+    /// The copy shares the data of its mods with the original
+    pub fn duplicate_profile(&mut self, profile: usize, name: String) {
+        let Some(original) = self.profiles.get(&profile) else {
+            error!("Could not find profile {profile} to duplicate");
+            return;
+        };
+
+        for path in original.data_paths() {
+            self.store.add_reference(path);
+        }
+        let duplicate = Profile {
+            name,
+            mods: original.mods.clone(),
+            conflict_resolutions: original.conflict_resolutions.clone(),
+            load_order_rules: original.load_order_rules.clone(),
+            manifest_path: None,
+            parent: original.parent,
+            overlay: original.overlay.clone(),
+            history: ProfileHistory::default(),
+        };
+        self.profiles.insert(self.next_profile, duplicate);
+        self.current_profile = self.next_profile;
+        self.next_profile += 1;
+    }
+
+    /// Trashes the data of its mods that no other profile uses, the last profile cannot be deleted.
+    /// Profiles inheriting from it inherit from its parent instead
+    pub fn delete_profile(&mut self, profile: usize) {
+        self.remove_profile(profile, true);
+    }
+
+    /// Deletes the profile but leaves the data of its mods in the library
+    pub fn delete_profile_keeping_data(&mut self, profile: usize) {
+        self.remove_profile(profile, false);
+    }
+
+    fn remove_profile(&mut self, profile: usize, discard_data: bool) {
+        if self.profiles.len() <= 1 {
+            error!("Could not delete the only profile");
+            return;
+        }
+        let Some(deleted) = self.profiles.remove(&profile) else {
+            error!("Could not find profile {profile} to delete");
+            return;
+        };
+
+        if self.current_profile == profile {
+            self.current_profile = *self.profiles.keys().min().unwrap();
+        }
+
+        for path in deleted.data_paths() {
+            if discard_data {
+                self.store.discard(path);
+            } else {
+                self.store.remove_reference(path);
+            }
+        }
+
+        let children = self.profiles.iter()
+            .filter(|(_, child)| child.parent == Some(profile))
+            .map(|(index, _)| *index)
+            .collect::<Vec<usize>>();
+        for child in children {
+            self.profiles.get_mut(&child).unwrap().parent = deleted.parent;
+            self.profile_changed(child);
+        }
+        self.empty_trash();
+    }
+
     pub fn switch_profile(&mut self, profile: usize) {
         if self.profiles.contains_key(&profile) {
             self.current_profile = profile;
@@ -74,7 +596,66 @@ impl Profiles {
     }
 
     pub fn set_mod_enabled_state(&mut self, mod_index: usize, enabled: bool) {
+        let previous_resolutions = self.conflict_resolutions().clone();
+        let Some(previous_mod) = self.get_mods().get(mod_index).cloned() else {
+            error!("Could not find mod to disable at position {mod_index}");
+            return;
+        };
         self.profiles.get_mut(&self.current_profile).unwrap().set_mod_enabled_state(mod_index, enabled);
+        self.finish_edit(self.current_profile, ProfileEdit::Changed(vec![(mod_index, previous_mod)]), previous_resolutions);
+    }
+
+    pub fn set_mod_notes(&mut self, mod_index: usize, notes: String) {
+        let Some(mod_state) = self.profiles.get_mut(&self.current_profile).unwrap().mods.get_mut(mod_index) else {
+            error!("Could not find mod to add notes to at position {mod_index}");
+            return;
+        };
+        mod_state.notes = notes;
+    }
+
+    pub fn move_mods(&mut self, start: usize, count: usize, position: usize) {
+        let previous_resolutions = self.conflict_resolutions().clone();
+        if !self.profiles.get_mut(&self.current_profile).unwrap().move_mods(start, count, position) { return }
+
+        let mut order = (0..self.get_mods().len()).collect::<Vec<usize>>();
+        let moved_positions = order.drain(start..start + count).collect::<Vec<usize>>();
+        order.splice(position..position, moved_positions);
+        self.finish_edit(self.current_profile, ProfileEdit::Reordered(order), previous_resolutions);
+    }
+
+    pub fn reorder_mods(&mut self, order: &[usize]) {
+        let previous_resolutions = self.conflict_resolutions().clone();
+        if !self.profiles.get_mut(&self.current_profile).unwrap().reorder_mods(order) { return }
+        self.finish_edit(self.current_profile, ProfileEdit::Reordered(order.to_vec()), previous_resolutions);
+    }
+
+    pub fn set_conflict_resolution(&mut self, file_path: &str, mod_index: Option<usize>) {
+        self.profiles.get_mut(&self.current_profile).unwrap().set_conflict_resolution(file_path, mod_index);
+    }
+
+    pub fn conflict_resolutions(&self) -> &HashMap<String, PathBuf> {
+        &self.profiles.get(&self.current_profile).unwrap().conflict_resolutions
+    }
+
+    pub fn load_order_rules(&self) -> &HashMap<String, Vec<LoadOrderRule>> {
+        &self.profiles.get(&self.current_profile).unwrap().load_order_rules
+    }
+
+    pub fn manifest_path(&self) -> Option<&Path> {
+        self.profiles.get(&self.current_profile).unwrap().manifest_path.as_deref()
+    }
+
+    pub fn set_manifest_path(&mut self, path: &Path) {
+        self.profiles.get_mut(&self.current_profile).unwrap().manifest_path = Some(path.to_owned());
+    }
+
+    pub fn set_load_order_rules(&mut self, uuid: String, rules: Vec<LoadOrderRule>) {
+        let load_order_rules = &mut self.profiles.get_mut(&self.current_profile).unwrap().load_order_rules;
+        if rules.is_empty() {
+            load_order_rules.remove(&uuid);
+        } else {
+            load_order_rules.insert(uuid, rules);
+        }
     }
 }
 
@@ -83,6 +664,22 @@ impl Profiles {
 struct Profile {
     name: String,
     mods: Vec<ModState>,
+    /// Lowercase packaged file paths mapped to the data dir of the mod chosen to provide them
+    #[serde(default)]
+    conflict_resolutions: HashMap<String, PathBuf>,
+    /// Rules of mods in this profile keyed by their lowercase UUID, kept when a mod is removed
+    #[serde(default)]
+    load_order_rules: HashMap<String, Vec<LoadOrderRule>>,
+    /// Manifest the profile was built from or last written to
+    #[serde(default)]
+    manifest_path: Option<PathBuf>,
+    /// Profile whose mods this profile inherits, `mods` holds them resolved with the local changes in `overlay`
+    #[serde(default)]
+    parent: Option<usize>,
+    #[serde(default)]
+    overlay: ProfileOverlay,
+    #[serde(default)]
+    history: ProfileHistory,
 }
 
 impl Profile {
@@ -90,6 +687,12 @@ impl Profile {
         Profile {
             name,
             mods: Vec::new(),
+            conflict_resolutions: HashMap::new(),
+            load_order_rules: HashMap::new(),
+            manifest_path: None,
+            parent: None,
+            overlay: ProfileOverlay::default(),
+            history: ProfileHistory::default(),
         }
     }
 
@@ -98,24 +701,42 @@ impl Profile {
             meta,
             path: unpacked_data_path.to_owned(),
             enabled: true,
+            notes: String::new(),
+            previous_versions: Vec::new(),
         })
     }
 
-    fn remove_mod(&mut self, mod_index: usize) {
-        let Some(mod_state) = self.mods.get(mod_index) else {
-            error!("Could not find mod at position {mod_index}");
-            return;
-        };
+    fn insert_mod(&mut self, position: usize, unpacked_data_path: &Path, meta: Option<Meta>) {
+        self.mods.insert(position.min(self.mods.len()), ModState {
+            meta,
+            path: unpacked_data_path.to_owned(),
+            enabled: true,
+            notes: String::new(),
+            previous_versions: Vec::new(),
+        })
+    }
 
-        let has_duplicates = self.mods.iter().enumerate().any(|(index, state)| {
-            state.path == mod_state.path && index != mod_index
-        });
+    /// Every data dir the profile uses or retains, once per reference
+    fn data_paths(&self) -> impl Iterator<Item = &Path> {
+        Profile::mod_data_paths(&self.mods)
+    }
 
-        if !has_duplicates && fs::remove_dir_all(&mod_state.path).is_err() {
-            error!("Could not remove mod data dir {}", &mod_state.path.to_string_lossy());
+    fn mod_data_paths(mods: &[ModState]) -> impl Iterator<Item = &Path> {
+        mods.iter().flat_map(|mod_state| std::iter::once(mod_state.path.as_path())
+            .chain(mod_state.previous_versions.iter().map(|version| version.path.as_path())))
+    }
+
+    fn remove_mod(&mut self, mod_index: usize) -> Option<ModState> {
+        if mod_index >= self.mods.len() {
+            error!("Could not find mod at position {mod_index}");
+            return None;
         }
 
-        self.mods.remove(mod_index);
+        let removed_mod = self.mods.remove(mod_index);
+        if !self.mods.iter().any(|mod_state| mod_state.path == removed_mod.path) {
+            self.conflict_resolutions.retain(|_, path| *path != removed_mod.path);
+        }
+        Some(removed_mod)
     }
 
     pub fn set_mod_enabled_state(&mut self, mod_index: usize, enabled: bool) {
@@ -126,4 +747,180 @@ impl Profile {
 
         mod_state.enabled = enabled;
     }
-}
\ No newline at end of file
+
+    /// Moves `count` mods starting at `start` so the first of them ends up at `position`, returns whether they were moved
+    fn move_mods(&mut self, start: usize, count: usize, position: usize) -> bool {
+        if count == 0 || start + count > self.mods.len() || position + count > self.mods.len() {
+            error!("Could not move {count} mods from position {start} to {position}");
+            return false;
+        }
+
+        let moved_mods = self.mods.drain(start..start + count).collect::<Vec<ModState>>();
+        self.mods.splice(position..position, moved_mods);
+        true
+    }
+
+    /// `order` lists every current position once, in the new order, returns whether the mods were reordered
+    fn reorder_mods(&mut self, order: &[usize]) -> bool {
+        let mut is_permutation = order.len() == self.mods.len();
+        let mut seen = vec![false; self.mods.len()];
+        for &index in order {
+            is_permutation &= index < seen.len() && !std::mem::replace(&mut seen[index], true);
+        }
+        if !is_permutation {
+            error!("Could not reorder mods, {order:?} is not a valid order");
+            return false;
+        }
+
+        let mut mods = self.mods.drain(..).map(Some).collect::<Vec<Option<ModState>>>();
+        self.mods = order.iter().map(|&index| mods[index].take().unwrap()).collect();
+        true
+    }
+
+    fn redirect_conflict_resolutions(&mut self, old_path: &Path, new_path: &Path) {
+        for chosen_path in self.conflict_resolutions.values_mut() {
+            if chosen_path == old_path {
+                *chosen_path = new_path.to_owned();
+            }
+        }
+    }
+
+    fn set_conflict_resolution(&mut self, file_path: &str, mod_index: Option<usize>) {
+        let file_path = file_path.to_ascii_lowercase();
+        let Some(mod_index) = mod_index else {
+            self.conflict_resolutions.remove(&file_path);
+            return;
+        };
+
+        let Some(mod_state) = self.mods.get(mod_index) else {
+            error!("Could not find mod to resolve conflict with at position {mod_index}");
+            return;
+        };
+
+        self.conflict_resolutions.insert(file_path, mod_state.path.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use models::{MergeChange, ProfileDiffError};
+    use crate::state::helpers::{HashHelper, PathHelper};
+    use crate::state::mod_store::ModStore;
+    use crate::state::test_mods::{test_data_dir, test_mod, uuids};
+    use super::Profiles;
+
+    /// Profile 0 has a, b, c and x with c disabled, profile 1 has b, a and c and is the current profile
+    fn merge_profiles() -> Profiles {
+        let mut profiles = Profiles::new();
+        profiles.add_profile("Source".into());
+        for uuid in ["a", "b", "c", "x"] {
+            let mod_state = test_mod(uuid, &[]);
+            profiles.add_mod(&mod_state.path, mod_state.meta);
+        }
+        profiles.set_mod_enabled_state(2, false);
+
+        profiles.add_profile("Target".into());
+        for uuid in ["b", "a", "c"] {
+            let mod_state = test_mod(uuid, &[]);
+            profiles.add_mod(&mod_state.path, mod_state.meta);
+        }
+        profiles
+    }
+
+    #[test]
+    fn merges_selected_changes() {
+        test_data_dir("merges_selected_changes");
+        let mut profiles = merge_profiles();
+        let changes = [
+            MergeChange::AddMod { id: "x".into() },
+            MergeChange::Enabled { id: "c".into() },
+            MergeChange::Order { id: "b".into() },
+        ];
+        profiles.merge_profiles(0, 1, &changes, 3).unwrap();
+
+        let target = &profiles.profiles[&1].mods;
+        assert_eq!(uuids(target), ["a", "b", "c", "x"]);
+        assert!(!target[2].enabled);
+
+        // The merge is a single edit
+        assert!(profiles.undo());
+        assert_eq!(uuids(profiles.get_mods()), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn rejects_invalid_changes_without_merging() {
+        test_data_dir("rejects_invalid_changes_without_merging");
+        let mut profiles = merge_profiles();
+        let invalid_changes = [
+            // The first add is valid on its own
+            vec![MergeChange::AddMod { id: "x".into() }, MergeChange::AddMod { id: "x".into() }],
+            // a is in the source, so a merge cannot remove it
+            vec![MergeChange::Enabled { id: "c".into() }, MergeChange::RemoveMod { id: "a".into() }],
+            vec![MergeChange::Version { id: "missing".into() }],
+        ];
+
+        for changes in invalid_changes {
+            let result = profiles.merge_profiles(0, 1, &changes, 3);
+            assert!(matches!(result, Err(ProfileDiffError::InvalidChange(_))));
+
+            let target = &profiles.profiles[&1].mods;
+            assert_eq!(uuids(target), ["b", "a", "c"]);
+            assert!(target.iter().all(|mod_state| mod_state.enabled));
+        }
+        assert!(matches!(profiles.merge_profiles(0, 7, &[], 3), Err(ProfileDiffError::ProfileNotFound(7))));
+
+        // Nothing was recorded, the last edit is still adding c
+        assert!(profiles.undo());
+        assert_eq!(uuids(profiles.get_mods()), ["b", "a"]);
+    }
+
+    /// A mod dir of the layout before the hashed store, `Mods/<profile name>/<file stem>`
+    fn old_layout_dir(profile: &str, stem: &str, package: Option<&str>) -> PathBuf {
+        let dir = PathHelper::get_mod_store_dir().join(profile).join(stem);
+        fs::create_dir_all(&dir).unwrap();
+        match package {
+            Some(content) => fs::write(dir.join(format!("{stem}.pak")), content).unwrap(),
+            None => fs::write(dir.join("readme.txt"), "no package").unwrap(),
+        }
+        dir
+    }
+
+    #[test]
+    fn migrates_profiles_sharing_a_package() {
+        test_data_dir("migrates_profiles_sharing_a_package");
+        let mut profiles = Profiles::new();
+        profiles.add_profile("First".into());
+        profiles.add_mod(&old_layout_dir("First", "Shared", Some("shared")), None);
+        let unused = old_layout_dir("First", "Unused", Some("unused"));
+        profiles.add_profile("Second".into());
+        profiles.add_mod(&old_layout_dir("Second", "Shared", Some("shared")), None);
+        let broken = old_layout_dir("Second", "Broken", None);
+        profiles.add_mod(&broken, None);
+        profiles.store = ModStore::default();
+
+        profiles.init();
+        let shared = profiles.profiles[&0].mods[0].path.clone();
+        assert_eq!(profiles.profiles[&1].mods[0].path, shared);
+        assert!(HashHelper::is_hash(&shared.file_name().unwrap().to_string_lossy()));
+        assert!(shared.join("Shared.pak").exists());
+        assert!(profiles.store.is_referenced(&shared));
+        assert!(ModStore::data_dirs().any(|dir| dir.join("Unused.pak").exists()));
+        assert!(!unused.exists());
+        assert!(!PathHelper::get_mod_store_dir().join("First").exists());
+
+        // The dir without a package is kept where it was and the migration is retried on the next start
+        assert_eq!(profiles.profiles[&1].mods[1].path, broken);
+        assert!(broken.exists());
+        assert!(profiles.needs_store_migration());
+
+        fs::write(broken.join("Broken.pak"), "fixed").unwrap();
+        profiles.init();
+        let fixed = &profiles.profiles[&1].mods[1].path;
+        assert!(fixed.join("Broken.pak").exists());
+        assert!(!PathHelper::get_mod_store_dir().join("Second").exists());
+        assert_eq!(profiles.profiles[&0].mods[0].path, shared);
+        assert!(!profiles.needs_store_migration());
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use package_helper::PackageWriter;
+use crate::{error, info};
+use crate::state::conflicts::{AnalysedMod, ConflictAnalyser};
+use crate::state::helpers::PathHelper;
+
+/// A generated package holding the files the user chose to resolve file conflicts with
+pub(crate) struct ResolutionPackage;
+
+impl ResolutionPackage {
+    pub const FILE_NAME: &'static str = "OneHornModManager_Resolutions.pak";
+    // Packages with a higher priority override the files of every lower priority package
+    const PRIORITY: u8 = u8::MAX;
+
+    /// Rebuilds the resolution package in the data dir, returns `None` when there is nothing to resolve
+    pub fn build(mods: &[AnalysedMod], resolutions: &HashMap<String, PathBuf>) -> Option<PathBuf> {
+        let package_path = PathHelper::get_data_dir().join(Self::FILE_NAME);
+        if package_path.exists() {
+            if let Err(error) = fs::remove_file(&package_path) {
+                error!("Could not remove old resolution package: {error}");
+                return None;
+            }
+        }
+
+        let mut writer = PackageWriter::new(Self::PRIORITY);
+        for analysed_mod in mods {
+            let chosen_files = resolutions.iter()
+                .filter(|(_, mod_path)| **mod_path == analysed_mod.path)
+                .map(|(file_path, _)| file_path.as_str())
+                .collect::<HashSet<&str>>();
+            if chosen_files.is_empty() { continue }
+
+            let Ok(package) = ConflictAnalyser::read_package(analysed_mod) else { continue };
+            let files = match package.read_files(|file| chosen_files.contains(file.name().to_ascii_lowercase().as_str())) {
+                Ok(files) => files,
+                Err(error) => {
+                    error!("Could not read resolved files from '{}': {error:?}", analysed_mod.name);
+                    continue;
+                }
+            };
+
+            for (file_info, contents) in files {
+                writer.add_file(file_info.name().to_string(), contents);
+            }
+        }
+
+        if writer.is_empty() {
+            return None;
+        }
+
+        info!("Writing conflict resolution package");
+        if let Err(error) = writer.write(&package_path) {
+            error!("Could not write resolution package: {error:?}");
+            return None;
+        }
+        Some(package_path)
+    }
+}
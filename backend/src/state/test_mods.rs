@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::PathBuf;
+use package_helper::Meta;
+use serde_json::json;
+use crate::state::helpers::PathHelper;
+use crate::state::mod_models::ModState;
+
+/// An empty data dir for the current test, named after the test so tests running in parallel do not share it
+pub(crate) fn test_data_dir(test_name: &str) -> PathBuf {
+    let data_dir = std::env::temp_dir().join(format!("one-horn-mod-manager-test-{}-{test_name}", std::process::id()));
+    if data_dir.exists() {
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+    fs::create_dir_all(&data_dir).unwrap();
+    PathHelper::set_test_data_dir(&data_dir);
+    data_dir
+}
+
+/// An enabled mod stored in `Mods/<uuid>` that depends on the mods with the `dependencies` UUIDs
+pub(crate) fn test_mod(uuid: &str, dependencies: &[&str]) -> ModState {
+    let property = |value: &str| json!({ "value_type": "LSString", "value": value });
+    let meta = json!({
+        "name": property(uuid),
+        "description": "",
+        "folder": property(uuid),
+        "uuid": property(uuid),
+        "md5": property(""),
+        "version": { "major": 1, "minor": 0, "revision": 0, "build": 0 },
+        "dependencies": dependencies.iter()
+            .map(|dependency| json!({ "uuid": dependency, "name": dependency, "folder": dependency, "version64": 0 }))
+            .collect::<Vec<_>>(),
+    });
+
+    ModState {
+        meta: Some(serde_json::from_value::<Meta>(meta).unwrap()),
+        path: PathBuf::from("Mods").join(uuid),
+        enabled: true,
+        notes: String::new(),
+        previous_versions: Vec::new(),
+    }
+}
+
+/// UUIDs of the mods in order
+pub(crate) fn uuids(mods: &[ModState]) -> Vec<&str> {
+    mods.iter().map(|mod_state| mod_state.meta.as_ref().unwrap().uuid().value()).collect()
+}
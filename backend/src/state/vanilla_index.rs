@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use serde::{Deserialize, Serialize};
+use models::{ConflictingMod, VanillaFile, VanillaOverrides, VanillaOverridesError};
+use package_helper::PackageReader;
+use crate::{error, info, warn};
+use crate::state::conflicts::AnalysedMod;
+use crate::state::content_index::ContentIndex;
+use crate::state::helpers::PathHelper;
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct VanillaPackageSignature {
+    path: PathBuf,
+    size: u64,
+    modified: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VanillaPackage {
+    signature: VanillaPackageSignature,
+    files: Vec<String>,
+}
+
+/// The file lists of the game's own packages, cached in the data dir until the game is patched
+#[derive(Serialize, Deserialize)]
+pub(crate) struct VanillaIndex {
+    game_data_dir: PathBuf,
+    packages: Vec<VanillaPackage>,
+}
+
+impl VanillaIndex {
+    const CACHE_FILE_NAME: &'static str = "vanilla_index.json";
+
+    pub fn load_or_build(game_data_dir: &Path) -> Result<VanillaIndex, VanillaOverridesError> {
+        if !game_data_dir.is_dir() {
+            error!("Game data dir {} does not exist", game_data_dir.to_string_lossy());
+            return Err(VanillaOverridesError::GameDataDirNotFound);
+        }
+
+        let signatures = Self::find_package_signatures(game_data_dir);
+        if signatures.is_empty() {
+            error!("No packages found in game data dir {}", game_data_dir.to_string_lossy());
+            return Err(VanillaOverridesError::NoVanillaPackagesFound);
+        }
+
+        if let Some(index) = Self::load_cache() {
+            let is_current = index.game_data_dir == game_data_dir
+                && index.packages.len() == signatures.len()
+                && index.packages.iter().zip(&signatures).all(|(package, signature)| package.signature == *signature);
+            if is_current {
+                info!("Using cached vanilla file index");
+                return Ok(index);
+            }
+        }
+
+        info!("Indexing vanilla packages");
+        let mut packages = Vec::with_capacity(signatures.len());
+        for signature in signatures {
+            let package = match PackageReader::read_package(&game_data_dir.join(&signature.path)) {
+                Ok(package) => package,
+                Err(error) => {
+                    warn!("Skipping unreadable vanilla package {}: {error:?}", signature.path.to_string_lossy());
+                    continue;
+                }
+            };
+            packages.push(VanillaPackage {
+                signature,
+                files: package.files().iter().map(|file| file.name().to_ascii_lowercase()).collect(),
+            });
+        }
+
+        let index = VanillaIndex {
+            game_data_dir: game_data_dir.to_owned(),
+            packages,
+        };
+        index.save_cache();
+        Ok(index)
+    }
+
+    pub fn find_overrides(&self, mods: &[AnalysedMod], index: &ContentIndex) -> Result<Vec<VanillaOverrides>, VanillaOverridesError> {
+        let mut vanilla_files = HashMap::new();
+        for package in &self.packages {
+            let package_name = package.signature.path.to_string_lossy().to_string();
+            for file in &package.files {
+                vanilla_files.insert(file.as_str(), package_name.clone());
+            }
+        }
+
+        let mut overrides = Vec::new();
+        for analysed_mod in mods {
+            let indexed_mod = index.get(&analysed_mod.path).ok_or_else(|| {
+                error!("Mod '{}' is not in the content index", analysed_mod.name);
+                VanillaOverridesError::CannotReadModPackage { mod_name: analysed_mod.name.clone() }
+            })?;
+
+            let files = indexed_mod.files.iter()
+                .filter_map(|file| Some(VanillaFile {
+                    path: file.name.clone(),
+                    vanilla_package: vanilla_files.get(file.name.to_ascii_lowercase().as_str())?.clone(),
+                }))
+                .collect::<Vec<VanillaFile>>();
+
+            if !files.is_empty() {
+                overrides.push(VanillaOverrides {
+                    overriding_mod: ConflictingMod::from(analysed_mod),
+                    files,
+                });
+            }
+        }
+        Ok(overrides)
+    }
+
+    fn find_package_signatures(game_data_dir: &Path) -> Vec<VanillaPackageSignature> {
+        let mut package_paths = Vec::new();
+        Self::find_packages(game_data_dir, &mut package_paths);
+        package_paths.sort();
+
+        package_paths.iter()
+            .filter(|path| !Self::is_package_part(path))
+            .filter_map(|path| {
+                let metadata = fs::metadata(path).ok()?;
+                let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+                Some(VanillaPackageSignature {
+                    path: path.strip_prefix(game_data_dir).ok()?.to_owned(),
+                    size: metadata.len(),
+                    modified,
+                })
+            })
+            .collect()
+    }
+
+    fn find_packages(dir: &Path, package_paths: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                Self::find_packages(&path, package_paths);
+            } else if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("pak")) {
+                package_paths.push(path);
+            }
+        }
+    }
+
+    /// Parts of split packages (`Gustav_1.pak`) are read through their main package
+    fn is_package_part(path: &Path) -> bool {
+        let Some(stem) = path.file_stem().map(|stem| stem.to_string_lossy()) else { return false };
+        let Some((base, part)) = stem.rsplit_once('_') else { return false };
+
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) && path.with_file_name(format!("{base}.pak")).exists()
+    }
+
+    fn load_cache() -> Option<VanillaIndex> {
+        let cache = fs::read_to_string(PathHelper::get_data_dir().join(Self::CACHE_FILE_NAME)).ok()?;
+        match serde_json::from_str(&cache) {
+            Ok(index) => Some(index),
+            Err(error) => {
+                warn!("Ignoring invalid vanilla index cache: {error}");
+                None
+            }
+        }
+    }
+
+    fn save_cache(&self) {
+        let cache = match serde_json::to_string(self) {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!("Could not serialize vanilla index: {error:?}");
+                return;
+            }
+        };
+
+        if let Err(error) = fs::write(PathHelper::get_data_dir().join(Self::CACHE_FILE_NAME), cache) {
+            error!("Could not save vanilla index: {error:?}");
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct ConflictingMod {
+    pub index: usize,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct GuidConflict {
+    pub guid: String,
+    pub attribute: String,
+    pub node: String,
+    pub providers: Vec<ConflictingMod>,
+    pub winner: ConflictingMod,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct FileConflict {
+    pub path: String,
+    pub providers: Vec<ConflictingMod>,
+    pub winner: ConflictingMod,
+    /// Whether the winner was chosen by the user rather than by load order
+    pub resolved: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CollisionKind {
+    Uuid,
+    Folder,
+}
+
+/// Mods sharing the same UUID or Folder, `value` is the shared one in lowercase
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct ModCollision {
+    pub kind: CollisionKind,
+    pub value: String,
+    pub mods: Vec<ConflictingMod>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct VanillaFile {
+    pub path: String,
+    pub vanilla_package: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct VanillaOverrides {
+    pub overriding_mod: ConflictingMod,
+    pub files: Vec<VanillaFile>,
+}
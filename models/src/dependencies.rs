@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::ConflictingMod;
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum DependencyProblemKind {
+    NotInstalled,
+    Disabled,
+    VersionTooOld {
+        installed_version: String,
+        required_version: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct DependencyProblem {
+    pub uuid: String,
+    pub name: String,
+    pub kind: DependencyProblemKind,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct ModDependencyProblems {
+    pub dependent_mod: ConflictingMod,
+    pub problems: Vec<DependencyProblem>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum DependencySource {
+    /// A disabled mod of the current profile
+    Profile {
+        index: usize,
+    },
+    /// A mod of another profile or left in the mod store
+    Library {
+        mod_path: PathBuf,
+    },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AvailableDependency {
+    pub uuid: String,
+    pub name: String,
+    pub source: DependencySource,
+}
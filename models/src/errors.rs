@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::ApplyWarnings;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub enum AddModError {
@@ -52,4 +53,105 @@ pub enum ModDetailsError {
     CannotUnpackPackageFile,
     CannotReadPackageMeta,
     CannotFindPackageFile,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ConflictAnalysisError {
+    CannotFindPackageFile {
+        mod_name: String,
+    },
+    CannotReadPackage {
+        mod_name: String,
+    },
+    CannotReadPackagedFiles {
+        mod_name: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ApplyError {
+    /// Returned until the user confirms applying despite the warnings
+    NeedsConfirmation(ApplyWarnings),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum MasterlistError {
+    CannotReadFile,
+    InvalidMasterlist(String),
+    CannotSaveMasterlist,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ProfileManifestError {
+    CannotReadFile,
+    InvalidManifest(String),
+    CannotWriteFile,
+    /// The profile was not built from a manifest and no path was given
+    NoManifestPath,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ModpackError {
+    CannotReadFile,
+    InvalidModpack(String),
+    CannotReadMod {
+        mod_name: String,
+    },
+    CannotStoreMod {
+        mod_name: String,
+    },
+    /// The package in the modpack is not the one its manifest lists
+    HashMismatch {
+        mod_name: String,
+    },
+    CannotWriteFile,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum LoadOrderExchangeError {
+    CannotReadFile,
+    InvalidLoadOrder(String),
+    CannotWriteFile,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum GameModsImportError {
+    CannotStoreMod {
+        mod_name: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum LibraryError {
+    NotInLibrary,
+    ModInUse,
+    CannotDeleteMod,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ProfileDiffError {
+    ProfileNotFound(usize),
+    /// The change does not apply to these profiles, nothing was merged
+    InvalidChange(String),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum LoadOrderSortError {
+    /// Mod names in the order they depend on each other, the last mod depends on the first
+    DependencyCycle {
+        mods: Vec<String>,
+    },
+    /// Mod names forming a cycle only once user rules are applied
+    ContradictoryRules {
+        mods: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum VanillaOverridesError {
+    GameDataDirNotFound,
+    NoVanillaPackagesFound,
+    CannotReadModPackage {
+        mod_name: String,
+    },
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// A pak placed in the game's Mods folder without the manager
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct InstalledGameMod {
+    pub name: String,
+    pub file_name: String,
+    /// Whether modsettings.lsx activates it
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum GameModsImportMode {
+    /// Takes the paks out of the Mods folder
+    Move,
+    /// Leaves the paks in the Mods folder, the manager links its copies next to them
+    Copy,
+}
@@ -5,15 +5,31 @@ use serde::{Deserialize, Serialize};
 mod logging;
 mod errors;
 mod fs;
+mod conflicts;
+mod library;
+mod dependencies;
+mod load_order;
+mod masterlist;
+mod game_mods;
+mod profile_diff;
 
 pub use logging::*;
 pub use errors::*;
 pub use fs::*;
+pub use conflicts::*;
+pub use library::*;
+pub use dependencies::*;
+pub use load_order::*;
+pub use masterlist::*;
+pub use game_mods::*;
+pub use profile_diff::*;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Profiles {
     pub current_profile: usize,
     pub profiles: HashMap<usize, String>,
+    /// Parent of each profile that inherits the mods of another
+    pub parents: HashMap<usize, usize>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -21,7 +37,11 @@ pub struct Mod {
     pub name: String,
     pub description: String,
     pub version: String,
+    pub uuid: Option<String>,
     pub enabled: bool,
+    pub notes: String,
+    /// Versions retained to roll back to, newest first
+    pub previous_versions: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq)]
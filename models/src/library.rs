@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct LibraryFile {
+    pub mod_name: String,
+    pub mod_path: PathBuf,
+    pub path: String,
+    pub size: u64,
+}
+
+/// A mod in the mod store, whether or not a profile uses it
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct LibraryMod {
+    pub name: String,
+    pub description: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub size: u64,
+    /// The pak or zip the mod was added from
+    pub source: Option<PathBuf>,
+    /// Names of the profiles with this mod in their load order
+    pub profiles: Vec<String>,
+    /// Names of the profiles keeping this mod as a previous version to roll back to
+    pub retained_by: Vec<String>,
+    /// Used or retained by a profile, so it cannot be deleted
+    pub in_use: bool,
+    pub in_current_profile: bool,
+}
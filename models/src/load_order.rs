@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// A user rule attached to a mod, other mods are referenced by their UUID
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub enum LoadOrderRule {
+    LoadAfter {
+        uuid: String,
+    },
+    LoadBefore {
+        uuid: String,
+    },
+    PinTop,
+    PinBottom,
+}
+
+/// An entry of an imported load order that matches no mod in the library
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct MissingMod {
+    pub uuid: String,
+    pub name: String,
+}
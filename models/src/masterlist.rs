@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use crate::{ConflictingMod, ModCollision, ModDependencyProblems};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum MasterlistLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum MasterlistFindingKind {
+    Incompatible {
+        other: ConflictingMod,
+    },
+    WrongOrder {
+        should_load_after: ConflictingMod,
+    },
+    Message,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct MasterlistFinding {
+    pub subject: ConflictingMod,
+    pub level: MasterlistLevel,
+    pub kind: MasterlistFindingKind,
+    pub note: Option<String>,
+}
+
+/// Everything the user has to confirm before mods are applied
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct ApplyWarnings {
+    pub collisions: Vec<ModCollision>,
+    pub dependency_problems: Vec<ModDependencyProblems>,
+    pub masterlist_findings: Vec<MasterlistFinding>,
+}
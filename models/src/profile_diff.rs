@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// A mod of a compared profile, mods without a meta are identified by their data dir
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ComparedMod {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct VersionDifference {
+    pub compared_mod: ComparedMod,
+    pub version_a: String,
+    pub version_b: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct EnabledDifference {
+    pub compared_mod: ComparedMod,
+    pub enabled_a: bool,
+    pub enabled_b: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct OrderDifference {
+    pub compared_mod: ComparedMod,
+    pub position_a: usize,
+    pub position_b: usize,
+}
+
+/// Differences between profile A and profile B, order differences are the fewest mods to move in B to match the order of A
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct ProfileDiff {
+    pub only_in_a: Vec<ComparedMod>,
+    pub only_in_b: Vec<ComparedMod>,
+    pub version_differences: Vec<VersionDifference>,
+    pub enabled_differences: Vec<EnabledDifference>,
+    pub order_differences: Vec<OrderDifference>,
+}
+
+/// A difference to bring from the source profile into the target profile, the mod is referenced by its compared id
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub enum MergeChange {
+    /// Adds a mod only in the source after the mod it follows in the source
+    AddMod {
+        id: String,
+    },
+    /// Removes a mod only in the target
+    RemoveMod {
+        id: String,
+    },
+    /// Uses the version of the source, the version it replaces is retained to roll back to
+    Version {
+        id: String,
+    },
+    Enabled {
+        id: String,
+    },
+    /// Moves the mod after the mod it follows in the source
+    Order {
+        id: String,
+    },
+}
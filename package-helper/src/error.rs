@@ -35,4 +35,28 @@ pub enum PackageFileReadError {
     CouldNotDecompressZLibFile,
     CouldNotDecompressLZ4File,
     FileOffsetOverrunsFile,
+    InvalidArchivePart,
+    CannotOpenPackagePart,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum PackageWriteError {
+    CouldNotCreateFile,
+    CouldNotWriteFile,
+    FileNameTooLong,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ResourceReadError {
+    UnsupportedResourceType,
+    ResourceNotValidUtf8,
+    ResourceNotValidXml,
+    NoValidSignatureFound,
+    UnsupportedVersion(u32),
+    HeaderOverranEndOfFile,
+    SectionOverranEndOfFile,
+    UnsupportedCompressionMethod(u8),
+    CouldNotDecompressSection,
+    InvalidNameIndex,
+    ValueOverranEndOfSection,
 }
\ No newline at end of file
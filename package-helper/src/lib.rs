@@ -1,15 +1,20 @@
 
 mod models;
 mod package_reader;
+mod package_writer;
 mod package;
 mod file_info;
 mod error;
 mod meta;
+mod resource;
 
 pub use package::Package;
 pub use package_reader::PackageReader;
-pub use error::PackageReadError;
-pub use meta::{Meta, MetaProperty, Version};
+pub use package_writer::PackageWriter;
+pub use error::{PackageReadError, PackageFileReadError, PackageWriteError, ResourceReadError};
+pub use file_info::PackagedFileInfo;
+pub use resource::{Resource, ResourceNode, ResourceAttribute};
+pub use meta::{Dependency, Meta, MetaProperty, Version};
 
 
 
@@ -26,4 +31,192 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    const NAMES: [&str; 5] = ["Config", "ModuleInfo", "Name", "Folder", "UUID"];
+    const MOD_UUID: &str = "12345678-9abc-def0-1122-334455667788";
+    const MOD_UUID_BYTES: [u8; 16] = [0x78, 0x56, 0x34, 0x12, 0xbc, 0x9a, 0xf0, 0xde, 0x22, 0x11, 0x44, 0x33, 0x66, 0x55, 0x88, 0x77];
+
+    /// Names of a single bucket, name `index` is referenced as `index`
+    fn lsf_strings() -> Vec<u8> {
+        let mut strings = 1_u32.to_le_bytes().to_vec();
+        strings.extend_from_slice(&(NAMES.len() as u16).to_le_bytes());
+        for name in NAMES {
+            strings.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            strings.extend_from_slice(name.as_bytes());
+        }
+        strings
+    }
+
+    fn lsf_values() -> Vec<u8> {
+        let mut values = b"Test Mod\0".to_vec();
+        values.extend_from_slice(b"TestMod\0");
+        values.extend_from_slice(&MOD_UUID_BYTES);
+        values
+    }
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|value| value.to_le_bytes()).collect()
+    }
+
+    /// `Config` with a `ModuleInfo` child holding an LSString name, a FixedString folder and a guid
+    fn lsf_v6() -> Vec<u8> {
+        let strings = lsf_strings();
+        // Name, parent, next sibling, first attribute
+        let nodes = words(&[0, u32::MAX, u32::MAX, u32::MAX, 1, 0, u32::MAX, 0]);
+        // Name, type and length, next attribute, offset
+        let attributes = words(&[2, 23 | (9 << 6), 1, 0, 3, 22 | (8 << 6), 2, 9, 4, 31 | (16 << 6), u32::MAX, 17]);
+        let values = lsf_values();
+
+        let mut lsf = words(&[0x464F534C, 6]);
+        lsf.extend_from_slice(&0_u64.to_le_bytes());
+        lsf.extend(words(&[strings.len() as u32, 0, 0, 0, nodes.len() as u32, 0, attributes.len() as u32, 0, values.len() as u32, 0]));
+        lsf.extend_from_slice(&[0, 0, 0, 0]);
+        lsf.extend(words(&[1]));
+        for section in [strings, nodes, attributes, values] {
+            lsf.extend(section);
+        }
+        lsf
+    }
+
+    /// The same tree in the short format of version 3 without sibling data, with zlib compressed sections
+    fn lsf_v3_zlib() -> Vec<u8> {
+        let compress = |section: &[u8]| {
+            let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            std::io::Write::write_all(&mut encoder, section).unwrap();
+            encoder.finish().unwrap()
+        };
+        // Name, first attribute, parent
+        let nodes = words(&[0, u32::MAX, u32::MAX, 1, 0, 0]);
+        // Name, type and length, node
+        let attributes = words(&[2, 23 | (9 << 6), 1, 3, 22 | (8 << 6), 1, 4, 31 | (16 << 6), 1]);
+        let sections = [lsf_strings(), nodes, attributes, lsf_values()];
+
+        let mut lsf = words(&[0x464F534C, 3, 0]);
+        let compressed: Vec<Vec<u8>> = sections.iter().map(|section| compress(section)).collect();
+        for (section, compressed) in sections.iter().zip(&compressed) {
+            lsf.extend(words(&[section.len() as u32, compressed.len() as u32]));
+        }
+        lsf.extend_from_slice(&[1, 0, 0, 0]);
+        lsf.extend(words(&[0]));
+        for section in compressed {
+            lsf.extend(section);
+        }
+        lsf
+    }
+
+    fn assert_module_info(resource: &Resource) {
+        let nodes = resource.nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].name(), "Config");
+        assert_eq!(nodes[0].parent(), None);
+        assert!(nodes[0].attributes().is_empty());
+
+        assert_eq!(nodes[1].name(), "ModuleInfo");
+        assert_eq!(nodes[1].parent(), Some(0));
+        let attributes = nodes[1].attributes().iter()
+            .map(|attribute| (attribute.id(), attribute.value()))
+            .collect::<Vec<_>>();
+        assert_eq!(attributes, [("Name", Some("Test Mod")), ("Folder", Some("TestMod")), ("UUID", Some(MOD_UUID))]);
+    }
+
+    #[test]
+    fn reads_lsf() {
+        assert_module_info(&Resource::read("meta.lsf", &lsf_v6()).unwrap());
+    }
+
+    #[test]
+    fn reads_compressed_lsf() {
+        assert_module_info(&Resource::read("meta.lsf", &lsf_v3_zlib()).unwrap());
+    }
+
+    #[test]
+    fn ignores_oversized_uncompressed_sizes() {
+        // Sizes are only an upper bound, the node section claims 4 GiB here
+        let mut lsf = lsf_v3_zlib();
+        lsf[20..24].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_module_info(&Resource::read("meta.lsf", &lsf).unwrap());
+    }
+
+    #[test]
+    fn reads_lsx() {
+        let lsx = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<save>
+    <region id="Config">
+        <node id="Config">
+            <children>
+                <node id="ModuleInfo">
+                    <attribute id="Name" type="LSString" value="Test Mod"/>
+                    <attribute id="Folder" type="FixedString" value="TestMod"/>
+                    <attribute id="UUID" type="guid" value="{MOD_UUID}"/>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>"#);
+        // Larian tools write a byte order mark
+        let lsx = format!("\u{feff}{lsx}");
+        assert_module_info(&Resource::read("meta.lsx", lsx.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn rejects_corrupt_resources() {
+        let lsf = lsf_v6();
+        for length in [0, 6, 40, lsf.len() - 1] {
+            assert!(Resource::read("meta.lsf", &lsf[..length]).is_err(), "truncated at {length}");
+        }
+
+        let mut huge_section = lsf.clone();
+        huge_section[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(Resource::read("meta.lsf", &huge_section), Err(ResourceReadError::SectionOverranEndOfFile)));
+
+        let mut bad_name = lsf_v6();
+        let name_offset = lsf.len() - lsf_values().len() - 48 - 32;
+        bad_name[name_offset..name_offset + 4].copy_from_slice(&7_u32.to_le_bytes());
+        assert!(matches!(Resource::read("meta.lsf", &bad_name), Err(ResourceReadError::InvalidNameIndex)));
+
+        let mut bad_offset = lsf_v6();
+        let offset_offset = lsf.len() - lsf_values().len() - 48 + 12;
+        bad_offset[offset_offset..offset_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(Resource::read("meta.lsf", &bad_offset), Err(ResourceReadError::ValueOverranEndOfSection)));
+
+        let mut bad_zlib = lsf_v3_zlib();
+        let last = bad_zlib.len() - 1;
+        bad_zlib[last - 4..].fill(0xFF);
+        assert!(Resource::read("meta.lsf", &bad_zlib).is_err());
+
+        assert!(matches!(Resource::read("meta.lsx", b"<save><region"), Err(ResourceReadError::ResourceNotValidXml)));
+    }
+    #[test]
+    fn package_round_trip() {
+        let files = [
+            ("Mods/TestMod/meta.lsx".to_string(), b"<save></save>".repeat(20)),
+            ("Public/TestMod/Stats/Generated/Data/Spell.txt".to_string(), (0..=255_u8).cycle().take(5000).collect::<Vec<u8>>()),
+        ];
+        let mut writer = PackageWriter::new(3);
+        for (name, contents) in &files {
+            writer.add_file(name.clone(), contents.clone());
+        }
+        let package_path = std::env::temp_dir().join(format!("package-helper-round-trip-{}.pak", std::process::id()));
+        writer.write(&package_path).unwrap();
+
+        let package = PackageReader::read_package(&package_path).unwrap();
+        assert_eq!(package.files().len(), files.len());
+        let pak = std::fs::read(&package_path).unwrap();
+        for (file_info, (name, contents)) in package.files().iter().zip(&files) {
+            assert_eq!(file_info.name(), name);
+            assert_eq!(file_info.uncompressed_size(), contents.len());
+
+            let stored = &pak[file_info.offset_in_file()..file_info.offset_in_file() + file_info.size_on_disk()];
+            assert_eq!(&lz4_flex::decompress(stored, file_info.uncompressed_size()).unwrap(), contents);
+        }
+
+        let read_files = package.read_files(|_| true).unwrap();
+        assert_eq!(read_files.len(), files.len());
+        for ((file_info, read_contents), (name, contents)) in read_files.iter().zip(&files) {
+            assert_eq!(file_info.name(), name);
+            assert_eq!(read_contents, contents);
+        }
+
+        std::fs::remove_file(&package_path).unwrap();
+    }
 }
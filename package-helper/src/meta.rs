@@ -1,7 +1,8 @@
+use std::str::FromStr;
 use serde::{Serialize, Deserialize};
 use crate::error::MetaReadError;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct MetaProperty {
     value_type: String,
     value: String,
@@ -17,7 +18,7 @@ impl MetaProperty {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Version {
     major: u64,
     minor: u64,
@@ -31,13 +32,67 @@ impl ToString for Version {
     }
 }
 
+impl FromStr for Version {
+    type Err = MetaReadError;
+
+    /// Parses versions written as `major.minor.revision.build`, missing parts are 0
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let mut parts = version.trim().split('.').map(str::parse::<u64>);
+        let mut next_part = || parts.next().unwrap_or(Ok(0)).map_err(|_| MetaReadError::MetaDataInvalidVersion);
+
+        Ok(Version {
+            major: next_part()?,
+            minor: next_part()?,
+            revision: next_part()?,
+            build: next_part()?,
+        })
+    }
+}
+
 impl Version {
+    pub fn from_version64(version64: u64) -> Version {
+        Version {
+            major: version64 >> 55,
+            minor: (version64 >> 47) & 0xFF,
+            revision: (version64 >> 31) & 0xFFFF,
+            build: version64 & 0x7FFF_FFFF,
+        }
+    }
+
     pub fn version64(&self) -> u64 {
         (self.major << 55) | (self.minor << 47) | (self.revision << 31) | self.build
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A module listed in the `Dependencies` node of a meta.lsx
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Dependency {
+    uuid: String,
+    name: String,
+    folder: String,
+    version64: u64,
+}
+
+impl Dependency {
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn folder(&self) -> &str {
+        &self.folder
+    }
+
+    /// The minimum version required, 0 when any version will do
+    pub fn version64(&self) -> u64 {
+        self.version64
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Meta {
     name: MetaProperty,
     description: String,
@@ -45,6 +100,8 @@ pub struct Meta {
     uuid: MetaProperty,
     md5: MetaProperty,
     version: Version,
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
 }
 
 impl TryFrom<roxmltree::Document<'_>> for Meta {
@@ -57,9 +114,14 @@ impl TryFrom<roxmltree::Document<'_>> for Meta {
             MetaReadError::MetaDataMissingModuleInfo
         })?;
 
-        let version = Meta::read_version(&xml)?;
+        // Metas without a Version64 only have the version of the lsx format to go by
+        let version = match Meta::read_module_version(&module_info) {
+            Some(version) => version,
+            None => Meta::read_version(&xml)?,
+        };
+        let dependencies = Meta::read_dependencies(&xml);
 
-        Meta::from_module_info_node(module_info, version)
+        Meta::from_module_info_node(module_info, version, dependencies)
     }
 }
 
@@ -89,6 +151,7 @@ impl Meta {
                 revision: 0,
                 build: 0,
             },
+            dependencies: Vec::new(),
         }
     }
 
@@ -116,7 +179,11 @@ impl Meta {
         &self.md5
     }
 
-    fn from_module_info_node(module_info: roxmltree::Node, version: Version) -> Result<Meta, MetaReadError> {
+    pub fn dependencies(&self) -> &[Dependency] {
+        &self.dependencies
+    }
+
+    fn from_module_info_node(module_info: roxmltree::Node, version: Version, dependencies: Vec<Dependency>) -> Result<Meta, MetaReadError> {
         let name = Self::read_property(&module_info, "Name")?;
         let folder = Self::read_property(&module_info, "Folder")?;
         let uuid = Self::read_property(&module_info, "UUID")?;
@@ -135,9 +202,42 @@ impl Meta {
             uuid,
             md5,
             version,
+            dependencies,
         })
     }
 
+    fn read_module_version(module_info: &roxmltree::Node) -> Option<Version> {
+        let version64 = Self::read_property(module_info, "Version64").ok()?.value.parse::<u64>().ok()?;
+        Some(Version::from_version64(version64))
+    }
+
+    fn read_dependencies(xml: &roxmltree::Document) -> Vec<Dependency> {
+        let Some(dependencies) = xml.descendants().find(|n| n.attribute("id") == Some("Dependencies")) else {
+            return Vec::new();
+        };
+
+        dependencies.descendants()
+            .filter(|n| n.attribute("id") == Some("ModuleShortDesc"))
+            .filter_map(|module| {
+                let uuid = Self::read_property(&module, "UUID").ok()?.value;
+                if uuid.is_empty() { return None }
+
+                // Older metas only have a 32 bit Version which cannot be compared, treat it as any version
+                let version64 = Self::read_property(&module, "Version64")
+                    .ok()
+                    .and_then(|version| version.value.parse::<u64>().ok())
+                    .unwrap_or(0);
+
+                Some(Dependency {
+                    uuid,
+                    name: Self::read_property(&module, "Name").map(|name| name.value).unwrap_or_default(),
+                    folder: Self::read_property(&module, "Folder").map(|folder| folder.value).unwrap_or_default(),
+                    version64,
+                })
+            })
+            .collect()
+    }
+
     fn read_version(xml: &roxmltree::Document) -> Result<Version, MetaReadError> {
         let module_info = xml.descendants().find(|n| {
             n.has_tag_name("version")
@@ -28,6 +28,34 @@ impl Package {
         }
     }
 
+    pub fn files(&self) -> &[PackagedFileInfo] {
+        &self.files
+    }
+
+    /// Reads and decompresses every packaged file accepted by `filter`
+    pub fn read_files(&self, filter: impl Fn(&PackagedFileInfo) -> bool) -> Result<Vec<(&PackagedFileInfo, Vec<u8>)>, PackageFileReadError> {
+        let mut open_files: Vec<Option<File>> = Vec::with_capacity(self.package_files.len());
+        for _ in 0..self.package_files.len() {
+            open_files.push(None);
+        }
+
+        let mut contents = Vec::new();
+        for file_info in self.files.iter().filter(|file| filter(file)) {
+            let open_file = open_files.get_mut(file_info.archive_part())
+                .ok_or(PackageFileReadError::InvalidArchivePart)?;
+            if open_file.is_none() {
+                let path = self.package_files.get(file_info.archive_part())
+                    .ok_or(PackageFileReadError::InvalidArchivePart)?;
+                *open_file = Some(File::open(path).map_err(|_| PackageFileReadError::CannotOpenPackagePart)?);
+            }
+            let file = open_file.as_mut().ok_or(PackageFileReadError::CannotOpenPackagePart)?;
+
+            contents.push((file_info, Self::read_file(file, file_info)?));
+        }
+
+        Ok(contents)
+    }
+
     fn read_file(file: &mut File, file_info: &PackagedFileInfo) -> Result<Vec<u8>, PackageFileReadError> {
         file.seek(SeekFrom::Start(file_info.offset_in_file() as u64)).map_err(|_| PackageFileReadError::FileOffsetOverrunsFile)?;
         let mut buffer = vec![0; file_info.size_on_disk()];
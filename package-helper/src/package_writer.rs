@@ -0,0 +1,87 @@
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use byteorder::{LE, WriteBytesExt};
+use crate::error::PackageWriteError;
+use crate::file_info::FileInfoV18;
+
+/// Writes single part BG3 (v18) packages with LZ4 compressed files
+pub struct PackageWriter {
+    priority: u8,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl PackageWriter {
+    const SIGNATURE: u32 = 0x4B50534C;
+    const VERSION: u32 = 18;
+    const HEADER_SIZE: u64 = 4 + 4 + 8 + 4 + 1 + 1 + 16 + 2;
+    const FLAGS_LZ4: u8 = 2;
+
+    pub fn new(priority: u8) -> PackageWriter {
+        PackageWriter {
+            priority,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn add_file(&mut self, name: String, contents: Vec<u8>) {
+        self.files.push((name, contents));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn write(&self, package_path: &Path) -> Result<(), PackageWriteError> {
+        let file = File::create(package_path).map_err(|_| PackageWriteError::CouldNotCreateFile)?;
+        let mut file = BufWriter::new(file);
+
+        file.seek(SeekFrom::Start(Self::HEADER_SIZE)).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+
+        let mut file_list = Vec::with_capacity(FileInfoV18::size() * self.files.len());
+        let mut offset = Self::HEADER_SIZE;
+        for (name, contents) in &self.files {
+            let compressed = lz4_flex::compress(contents);
+            file.write_all(&compressed).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+
+            Self::write_file_info(&mut file_list, name, offset, compressed.len(), contents.len())?;
+            offset += compressed.len() as u64;
+        }
+
+        let compressed_file_list = lz4_flex::compress(&file_list);
+        file.write_u32::<LE>(self.files.len() as u32).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_u32::<LE>(compressed_file_list.len() as u32).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_all(&compressed_file_list).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+
+        file.seek(SeekFrom::Start(0)).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_u32::<LE>(Self::SIGNATURE).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_u32::<LE>(Self::VERSION).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_u64::<LE>(offset).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_u32::<LE>(8 + compressed_file_list.len() as u32).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_u8(0).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_u8(self.priority).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_all(&[0; 16]).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file.write_u16::<LE>(1).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+
+        file.flush().map_err(|_| PackageWriteError::CouldNotWriteFile)
+    }
+
+    fn write_file_info(file_list: &mut Vec<u8>, name: &str, offset: u64, size_on_disk: usize,
+                       uncompressed_size: usize) -> Result<(), PackageWriteError> {
+        // Names are stored in a fixed 256 byte field and must be null terminated
+        if name.len() >= 256 {
+            return Err(PackageWriteError::FileNameTooLong);
+        }
+        let mut name_bytes = [0_u8; 256];
+        name_bytes[..name.len()].copy_from_slice(name.as_bytes());
+        file_list.extend_from_slice(&name_bytes);
+
+        file_list.write_u32::<LE>(offset as u32).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file_list.write_u16::<LE>((offset >> 32) as u16).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file_list.write_u8(0).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file_list.write_u8(Self::FLAGS_LZ4).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file_list.write_u32::<LE>(size_on_disk as u32).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        file_list.write_u32::<LE>(uncompressed_size as u32).map_err(|_| PackageWriteError::CouldNotWriteFile)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+use std::io::{Cursor, Read};
+use byteorder::{LE, ReadBytesExt};
+use crate::error::ResourceReadError;
+use crate::resource::{Resource, ResourceAttribute, ResourceNode};
+
+const TYPE_STRING: u32 = 20;
+const TYPE_PATH: u32 = 21;
+const TYPE_FIXED_STRING: u32 = 22;
+const TYPE_LS_STRING: u32 = 23;
+const TYPE_WSTRING: u32 = 29;
+const TYPE_LS_WSTRING: u32 = 30;
+const TYPE_UUID: u32 = 31;
+
+struct LsfMetadata {
+    strings: (u32, u32),
+    nodes: (u32, u32),
+    attributes: (u32, u32),
+    values: (u32, u32),
+    compression_flags: u8,
+    has_sibling_data: bool,
+}
+
+struct LsfNode {
+    name: String,
+    parent: i32,
+    first_attribute: i32,
+}
+
+struct LsfAttribute {
+    name: String,
+    type_id: u32,
+    length: u32,
+    offset: u32,
+    next_attribute: i32,
+    node: i32,
+}
+
+pub(crate) struct LsfReader;
+
+impl LsfReader {
+    const SIGNATURE: u32 = 0x464F534C;
+    const VERSION_CHUNKED_COMPRESS: u32 = 2;
+    const VERSION_EXTENDED_NODES: u32 = 3;
+    const VERSION_BG3_EXTENDED_HEADER: u32 = 5;
+    const VERSION_BG3_ADDITIONAL_BLOB: u32 = 6;
+    const VERSION_MAX: u32 = 7;
+
+    pub fn read(contents: &[u8]) -> Result<Resource, ResourceReadError> {
+        let mut file = Cursor::new(contents);
+
+        let signature = file.read_u32::<LE>().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+        if signature != Self::SIGNATURE {
+            return Err(ResourceReadError::NoValidSignatureFound);
+        }
+        let version = file.read_u32::<LE>().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+        if version == 0 || version > Self::VERSION_MAX {
+            return Err(ResourceReadError::UnsupportedVersion(version));
+        }
+
+        // Engine version
+        if version >= Self::VERSION_BG3_EXTENDED_HEADER {
+            file.read_u64::<LE>().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+        } else {
+            file.read_u32::<LE>().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+        }
+
+        let metadata = Self::read_metadata(&mut file, version)?;
+        let chunked = version >= Self::VERSION_CHUNKED_COMPRESS;
+
+        let strings = Self::read_section(&mut file, metadata.strings, metadata.compression_flags, false)?;
+        let nodes = Self::read_section(&mut file, metadata.nodes, metadata.compression_flags, chunked)?;
+        let attributes = Self::read_section(&mut file, metadata.attributes, metadata.compression_flags, chunked)?;
+        let values = Self::read_section(&mut file, metadata.values, metadata.compression_flags, chunked)?;
+
+        let long_format = version >= Self::VERSION_EXTENDED_NODES && metadata.has_sibling_data;
+        let names = Self::read_names(strings)?;
+        let nodes = Self::read_nodes(nodes, &names, long_format)?;
+        let attributes = Self::read_attributes(attributes, &names, long_format)?;
+
+        Self::build_resource(nodes, &attributes, &values, long_format)
+    }
+
+    fn read_metadata(file: &mut Cursor<&[u8]>, version: u32) -> Result<LsfMetadata, ResourceReadError> {
+        let mut read_sizes = || -> Result<(u32, u32), ResourceReadError> {
+            let uncompressed_size = file.read_u32::<LE>().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+            let size_on_disk = file.read_u32::<LE>().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+            Ok((uncompressed_size, size_on_disk))
+        };
+
+        let strings = read_sizes()?;
+        if version >= Self::VERSION_BG3_ADDITIONAL_BLOB {
+            // Node keys, these are stored after the values and are not needed
+            read_sizes()?;
+        }
+        let nodes = read_sizes()?;
+        let attributes = read_sizes()?;
+        let values = read_sizes()?;
+
+        let compression_flags = file.read_u8().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+        file.read_u8().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+        file.read_u16::<LE>().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)?;
+        let has_sibling_data = file.read_u32::<LE>().map_err(|_| ResourceReadError::HeaderOverranEndOfFile)? == 1;
+
+        Ok(LsfMetadata {
+            strings,
+            nodes,
+            attributes,
+            values,
+            compression_flags,
+            has_sibling_data,
+        })
+    }
+
+    fn read_section(file: &mut Cursor<&[u8]>, (uncompressed_size, size_on_disk): (u32, u32),
+                    compression_flags: u8, chunked: bool) -> Result<Vec<u8>, ResourceReadError> {
+        if size_on_disk == 0 && uncompressed_size == 0 {
+            return Ok(Vec::new());
+        }
+
+        // Sections that are stored uncompressed only record their uncompressed size
+        let stored_size = if size_on_disk == 0 { uncompressed_size } else { size_on_disk };
+        if u64::from(stored_size) > file.get_ref().len() as u64 - file.position() {
+            return Err(ResourceReadError::SectionOverranEndOfFile);
+        }
+        let mut buffer = vec![0; stored_size as usize];
+        file.read_exact(&mut buffer).map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+        if size_on_disk == 0 {
+            return Ok(buffer);
+        }
+
+        // The uncompressed size comes from the file, so buffers only grow with the data that is actually decompressed
+        let decompress = |decoder: &mut dyn Read| {
+            let mut uncompressed = Vec::new();
+            decoder.take(u64::from(uncompressed_size)).read_to_end(&mut uncompressed)
+                .map_err(|_| ResourceReadError::CouldNotDecompressSection)?;
+            Ok(uncompressed)
+        };
+
+        match compression_flags & 0xF {
+            0 => Ok(buffer),
+            1 => decompress(&mut flate2::read::ZlibDecoder::new(buffer.as_slice())),
+            2 if chunked => decompress(&mut lz4_flex::frame::FrameDecoder::new(buffer.as_slice())),
+            2 => {
+                // LZ4 blocks expand at most 255 times
+                let uncompressed_size = uncompressed_size.min(stored_size.saturating_mul(255));
+                lz4_flex::decompress(&buffer, uncompressed_size as usize)
+                    .map_err(|_| ResourceReadError::CouldNotDecompressSection)
+            }
+            method => Err(ResourceReadError::UnsupportedCompressionMethod(method)),
+        }
+    }
+
+    fn read_names(strings: Vec<u8>) -> Result<Vec<Vec<String>>, ResourceReadError> {
+        let mut strings = Cursor::new(strings);
+
+        let bucket_count = strings.read_u32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+        // Counts are not trusted for preallocation, a corrupt file could claim billions of names
+        let mut names = Vec::new();
+        for _ in 0..bucket_count {
+            let name_count = strings.read_u16::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+            let mut bucket = Vec::with_capacity(usize::from(name_count));
+            for _ in 0..name_count {
+                let length = strings.read_u16::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                let mut name = vec![0; length as usize];
+                strings.read_exact(&mut name).map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                bucket.push(String::from_utf8_lossy(&name).to_string());
+            }
+            names.push(bucket);
+        }
+        Ok(names)
+    }
+
+    fn resolve_name(names: &[Vec<String>], name_index: u32) -> Result<String, ResourceReadError> {
+        names.get((name_index >> 16) as usize)
+            .and_then(|bucket| bucket.get((name_index & 0xFFFF) as usize))
+            .cloned()
+            .ok_or(ResourceReadError::InvalidNameIndex)
+    }
+
+    fn read_nodes(nodes: Vec<u8>, names: &[Vec<String>], long_format: bool) -> Result<Vec<LsfNode>, ResourceReadError> {
+        let entry_size = if long_format { 16 } else { 12 };
+        let node_count = nodes.len() / entry_size;
+        let mut nodes = Cursor::new(nodes);
+
+        let mut entries = Vec::with_capacity(node_count);
+        for _ in 0..node_count {
+            let name_index = nodes.read_u32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+            let (parent, first_attribute) = if long_format {
+                let parent = nodes.read_i32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                let _next_sibling = nodes.read_i32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                let first_attribute = nodes.read_i32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                (parent, first_attribute)
+            } else {
+                let first_attribute = nodes.read_i32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                let parent = nodes.read_i32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                (parent, first_attribute)
+            };
+
+            entries.push(LsfNode {
+                name: Self::resolve_name(names, name_index)?,
+                parent,
+                first_attribute,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn read_attributes(attributes: Vec<u8>, names: &[Vec<String>], long_format: bool) -> Result<Vec<LsfAttribute>, ResourceReadError> {
+        let entry_size = if long_format { 16 } else { 12 };
+        let attribute_count = attributes.len() / entry_size;
+        let mut attributes = Cursor::new(attributes);
+
+        let mut entries = Vec::with_capacity(attribute_count);
+        // The short format does not store offsets, values are laid out in attribute order instead
+        let mut next_offset = 0;
+        for _ in 0..attribute_count {
+            let name_index = attributes.read_u32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+            let type_and_length = attributes.read_u32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+            let type_id = type_and_length & 0x3F;
+            let length = type_and_length >> 6;
+
+            let (offset, next_attribute, node) = if long_format {
+                let next_attribute = attributes.read_i32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                let offset = attributes.read_u32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                (offset, next_attribute, -1)
+            } else {
+                let node = attributes.read_i32::<LE>().map_err(|_| ResourceReadError::SectionOverranEndOfFile)?;
+                (next_offset, -1, node)
+            };
+            next_offset = offset.checked_add(length).ok_or(ResourceReadError::ValueOverranEndOfSection)?;
+
+            entries.push(LsfAttribute {
+                name: Self::resolve_name(names, name_index)?,
+                type_id,
+                length,
+                offset,
+                next_attribute,
+                node,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn build_resource(nodes: Vec<LsfNode>, attributes: &[LsfAttribute], values: &[u8],
+                      long_format: bool) -> Result<Resource, ResourceReadError> {
+        let mut node_attributes: Vec<Vec<&LsfAttribute>> = nodes.iter().map(|_| Vec::new()).collect();
+        if long_format {
+            for (node, node_attributes) in nodes.iter().zip(node_attributes.iter_mut()) {
+                let mut next = node.first_attribute;
+                // Bounded by the attribute count to guard against malformed chains
+                while let Some(attribute) = usize::try_from(next).ok().and_then(|next| attributes.get(next)) {
+                    if node_attributes.len() == attributes.len() { break }
+                    node_attributes.push(attribute);
+                    next = attribute.next_attribute;
+                }
+            }
+        } else {
+            for attribute in attributes {
+                if let Some(node_attributes) = usize::try_from(attribute.node).ok().and_then(|node| node_attributes.get_mut(node)) {
+                    node_attributes.push(attribute);
+                }
+            }
+        }
+
+        let mut resource_nodes = Vec::with_capacity(nodes.len());
+        for (node, attributes) in nodes.into_iter().zip(node_attributes) {
+            let mut resource_attributes = Vec::with_capacity(attributes.len());
+            for attribute in attributes {
+                let start = attribute.offset as usize;
+                let value = start.checked_add(attribute.length as usize).and_then(|end| values.get(start..end))
+                    .ok_or(ResourceReadError::ValueOverranEndOfSection)?;
+                resource_attributes.push(ResourceAttribute {
+                    id: attribute.name.clone(),
+                    value: Self::decode_value(attribute.type_id, value),
+                });
+            }
+
+            resource_nodes.push(ResourceNode {
+                name: node.name,
+                parent: usize::try_from(node.parent).ok(),
+                attributes: resource_attributes,
+            });
+        }
+
+        Ok(Resource { nodes: resource_nodes })
+    }
+
+    fn decode_value(type_id: u32, value: &[u8]) -> Option<String> {
+        match type_id {
+            TYPE_STRING | TYPE_PATH | TYPE_FIXED_STRING | TYPE_LS_STRING | TYPE_WSTRING | TYPE_LS_WSTRING => {
+                let end = value.iter().position(|byte| *byte == 0).unwrap_or(value.len());
+                Some(String::from_utf8_lossy(&value[..end]).to_string())
+            }
+            TYPE_UUID if value.len() == 16 => Some(Self::format_guid(value)),
+            _ => None,
+        }
+    }
+
+    fn format_guid(bytes: &[u8]) -> String {
+        let data1 = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let data2 = u16::from_le_bytes([bytes[4], bytes[5]]);
+        let data3 = u16::from_le_bytes([bytes[6], bytes[7]]);
+
+        // BG3 stores the last 8 bytes of a guid with each pair of bytes swapped
+        let mut data4 = [0_u8; 8];
+        for pair in 0..4 {
+            data4[pair * 2] = bytes[8 + pair * 2 + 1];
+            data4[pair * 2 + 1] = bytes[8 + pair * 2];
+        }
+
+        format!("{data1:08x}-{data2:04x}-{data3:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+                data4[0], data4[1], data4[2], data4[3], data4[4], data4[5], data4[6], data4[7])
+    }
+}
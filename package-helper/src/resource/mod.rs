@@ -0,0 +1,106 @@
+use crate::error::ResourceReadError;
+
+mod lsf;
+
+/// A flattened Larian resource tree read from either an LSX or LSF file
+pub struct Resource {
+    nodes: Vec<ResourceNode>,
+}
+
+pub struct ResourceNode {
+    name: String,
+    parent: Option<usize>,
+    attributes: Vec<ResourceAttribute>,
+}
+
+pub struct ResourceAttribute {
+    id: String,
+    value: Option<String>,
+}
+
+impl Resource {
+    pub fn is_resource_file(file_name: &str) -> bool {
+        let file_name = file_name.to_ascii_lowercase();
+        file_name.ends_with(".lsx") || file_name.ends_with(".lsf")
+    }
+
+    pub fn read(file_name: &str, contents: &[u8]) -> Result<Resource, ResourceReadError> {
+        let file_name = file_name.to_ascii_lowercase();
+        if file_name.ends_with(".lsx") {
+            Self::read_lsx(contents)
+        } else if file_name.ends_with(".lsf") {
+            lsf::LsfReader::read(contents)
+        } else {
+            Err(ResourceReadError::UnsupportedResourceType)
+        }
+    }
+
+    pub fn read_lsx(contents: &[u8]) -> Result<Resource, ResourceReadError> {
+        let contents = std::str::from_utf8(contents).map_err(|_| ResourceReadError::ResourceNotValidUtf8)?;
+        let contents = contents.trim_start_matches('\u{feff}');
+
+        let xml = roxmltree::Document::parse(contents).map_err(|_| ResourceReadError::ResourceNotValidXml)?;
+
+        let mut resource = Resource { nodes: Vec::new() };
+        for region in xml.descendants().filter(|n| n.has_tag_name("region")) {
+            for node in region.children().filter(|n| n.has_tag_name("node")) {
+                resource.read_lsx_node(node, None);
+            }
+        }
+        Ok(resource)
+    }
+
+    pub fn nodes(&self) -> &[ResourceNode] {
+        &self.nodes
+    }
+
+    fn read_lsx_node(&mut self, node: roxmltree::Node, parent: Option<usize>) {
+        let attributes = node.children()
+            .filter(|n| n.has_tag_name("attribute"))
+            .filter_map(|attribute| Some(ResourceAttribute {
+                id: attribute.attribute("id")?.to_string(),
+                value: attribute.attribute("value").map(ToString::to_string),
+            }))
+            .collect();
+
+        let index = self.nodes.len();
+        self.nodes.push(ResourceNode {
+            name: node.attribute("id").unwrap_or_default().to_string(),
+            parent,
+            attributes,
+        });
+
+        let children = node.children()
+            .filter(|n| n.has_tag_name("children"))
+            .flat_map(|children| children.children())
+            .filter(|n| n.has_tag_name("node"));
+        for child in children {
+            self.read_lsx_node(child, Some(index));
+        }
+    }
+}
+
+impl ResourceNode {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    pub fn attributes(&self) -> &[ResourceAttribute] {
+        &self.attributes
+    }
+}
+
+impl ResourceAttribute {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The attribute value as text, only string and guid values are decoded from LSF files
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+}
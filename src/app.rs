@@ -2,9 +2,11 @@ use std::rc::Rc;
 use yew::prelude::*;
 use crate::pages::main_page::MainPage;
 use crate::pages::file_explorer::FileExplorer;
+use crate::pages::library::Library;
 use crate::top_bar::TopBar;
 use crate::bottom_bar::BottomBar;
 use crate::console::Console;
+use crate::bindings::ModManager;
 use models::{Mod, Status};
 
 use crate::listeners;
@@ -14,6 +16,7 @@ pub fn app() -> Html {
     let mods: UseStateHandle<Status<Rc<Vec<Mod>>>> = use_state(|| Status::Loading);
 
     let file_explorer_open = use_state(|| false);
+    let library_open = use_state(|| false);
     let console_open = use_state(|| false);
     let selected_mod: UseStateHandle<Option<usize>> = use_state(|| None);
 
@@ -33,20 +36,38 @@ pub fn app() -> Html {
         });
     }, (dropped_file.clone(), profile_open.clone(), profile_create_new.clone(), file_explorer_open.clone()));
 
+    {
+        let mods = mods.clone();
+        let selected_mod = selected_mod.clone();
+        use_effect_with_deps(move |_| {
+            let (undo_mods, undo_selected_mod) = (mods.clone(), selected_mod.clone());
+            listeners::listen_for_undo(
+                move || ModManager::undo_profile_edit(undo_mods.clone(), undo_selected_mod.clone()),
+                move || ModManager::redo_profile_edit(mods.clone(), selected_mod.clone()),
+            );
+        }, ());
+    }
+
     html! {
         <div class="app">
             <div class="bg" />
             <TopBar
                 file_explorer_open={file_explorer_open.clone()}
+                library_open={library_open.clone()}
                 selected_mod={selected_mod.clone()}
                 mods={mods.clone()}
                 profile_open={profile_open.clone()}
                 profile_create_new={profile_create_new.clone()} />
             if *file_explorer_open {
                 <FileExplorer
+                    mods={mods.clone()}
                     file_explorer_open={file_explorer_open.clone()}
                     selected_mod={selected_mod.clone()}
                     dropped_file={dropped_file.clone()} />
+            } else if *library_open {
+                <Library
+                    mods={mods.clone()}
+                    library_open={library_open.clone()} />
             } else {
                 <MainPage
                     mods={mods.clone()}
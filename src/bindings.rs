@@ -4,7 +4,7 @@ use std::sync::Arc;
 use serde::{Serialize, Serializer};
 use yew::platform::spawn_local;
 use yew::UseStateHandle;
-use models::{FileBrowserRedirectError, FileEntry, MMResult, Mod, ModDetailsError, Profiles, Status};
+use models::{ApplyError, GameModsImportError, GameModsImportMode, InstalledGameMod, ModCollision, ApplyWarnings, AvailableDependency, ConflictAnalysisError, FileBrowserRedirectError, FileConflict, FileEntry, LibraryError, LibraryMod, LoadOrderRule, LoadOrderSortError, MMResult, MasterlistFinding, Mod, ModDetailsError, Profiles, Status};
 use crate::error;
 use tauri_sys::tauri;
 use wasm_bindgen::JsValue;
@@ -71,14 +71,54 @@ pub struct ModManager;
 
 
 impl ModManager {
-    pub fn apply(applying: UseStateHandle<bool>) {
+    pub fn apply(applying: UseStateHandle<bool>, confirmed: bool, apply_warnings: UseStateHandle<Option<Rc<ApplyWarnings>>>) {
+        #[derive(Serialize)]
+        struct Args { confirmed: bool }
         applying.set(true);
         spawn_local(async move {
-            let _: () = tauri::invoke("apply", &Null).await.unwrap();
+            match tauri::invoke("apply", &Args { confirmed }).await.unwrap() {
+                MMResult::Ok(()) => apply_warnings.set(None),
+                MMResult::Err(ApplyError::NeedsConfirmation(warnings)) => apply_warnings.set(Some(Rc::new(warnings))),
+            }
             applying.set(false);
         });
     }
 
+    pub fn get_masterlist_findings(findings: UseStateHandle<Rc<Vec<MasterlistFinding>>>) {
+        spawn_local(async move {
+            findings.set(Rc::new(tauri::invoke("get_masterlist_findings", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn get_load_order_rules(index: usize, rules: UseStateHandle<Rc<Vec<LoadOrderRule>>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize }
+        spawn_local(async move {
+            rules.set(Rc::new(tauri::invoke("get_load_order_rules", &Args { index }).await.unwrap()));
+        });
+    }
+
+    pub fn set_load_order_rules(index: usize, new_rules: Vec<LoadOrderRule>, rules: UseStateHandle<Rc<Vec<LoadOrderRule>>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize, rules: Vec<LoadOrderRule> }
+        spawn_local(async move {
+            let _: () = tauri::invoke("set_load_order_rules", &Args { index, rules: new_rules.clone() }).await.unwrap();
+            rules.set(Rc::new(new_rules));
+        });
+    }
+
+    pub fn sort_mods(mods: UseStateHandle<Status<Rc<Vec<Mod>>>>, sort_error: UseStateHandle<Option<LoadOrderSortError>>) {
+        spawn_local(async move {
+            match tauri::invoke("sort_mods", &Null).await.unwrap() {
+                MMResult::Ok(()) => {
+                    sort_error.set(None);
+                    mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+                },
+                MMResult::Err(error) => sort_error.set(Some(error)),
+            }
+        });
+    }
+
     pub fn remove_mod(index: usize, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
         #[derive(Serialize)]
         struct Args { index: usize }
@@ -88,9 +128,88 @@ impl ModManager {
         });
     }
 
-    pub fn add_mod() {
-        spawn_local(async {
-            let _: () = tauri::invoke("add_current_mod", &Null).await.unwrap();
+    pub fn undo_profile_edit(mods: UseStateHandle<Status<Rc<Vec<Mod>>>>, selected_mod: UseStateHandle<Option<usize>>) {
+        spawn_local(async move {
+            let undone: bool = tauri::invoke("undo_profile_edit", &Null).await.unwrap();
+            if undone {
+                selected_mod.set(None);
+                mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+            }
+        });
+    }
+
+    pub fn redo_profile_edit(mods: UseStateHandle<Status<Rc<Vec<Mod>>>>, selected_mod: UseStateHandle<Option<usize>>) {
+        spawn_local(async move {
+            let redone: bool = tauri::invoke("redo_profile_edit", &Null).await.unwrap();
+            if redone {
+                selected_mod.set(None);
+                mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+            }
+        });
+    }
+
+    pub fn add_mod(selected_mod: UseStateHandle<Option<usize>>) {
+        spawn_local(async move {
+            let index: Option<usize> = tauri::invoke("add_current_mod", &Null).await.unwrap();
+            selected_mod.set(index);
+        });
+    }
+
+    pub fn update_with_current_mod(index: usize, selected_mod: UseStateHandle<Option<usize>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize }
+        spawn_local(async move {
+            let index: Option<usize> = tauri::invoke("update_with_current_mod", &Args { index }).await.unwrap();
+            selected_mod.set(index);
+        });
+    }
+
+    pub fn rollback_mod(index: usize, version_index: usize, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize, version_index: usize }
+        spawn_local(async move {
+            let _: () = tauri::invoke("rollback_mod", &Args { index, version_index }).await.unwrap();
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn get_version_retention(retention: UseStateHandle<usize>) {
+        spawn_local(async move {
+            retention.set(tauri::invoke("get_version_retention", &Null).await.unwrap());
+        });
+    }
+
+    pub fn set_version_retention(new_retention: usize, retention: UseStateHandle<usize>, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
+        #[derive(Serialize)]
+        struct Args { retention: usize }
+        spawn_local(async move {
+            let _: () = tauri::invoke("set_version_retention", &Args { retention: new_retention }).await.unwrap();
+            retention.set(new_retention);
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn get_new_mod_collisions(collisions: UseStateHandle<Rc<Vec<ModCollision>>>) {
+        spawn_local(async move {
+            collisions.set(Rc::new(tauri::invoke("get_new_mod_collisions", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn get_available_dependencies(index: usize, available_dependencies: UseStateHandle<Rc<Vec<AvailableDependency>>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize }
+        spawn_local(async move {
+            available_dependencies.set(Rc::new(tauri::invoke("get_available_dependencies", &Args { index }).await.unwrap()));
+        });
+    }
+
+    pub fn add_dependencies(index: usize, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>, selected_mod: UseStateHandle<Option<usize>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize }
+        spawn_local(async move {
+            let index: usize = tauri::invoke("add_dependencies", &Args { index }).await.unwrap();
+            selected_mod.set(Some(index));
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
         });
     }
 
@@ -111,6 +230,25 @@ impl ModManager {
         });
     }
 
+    pub fn get_file_conflicts(conflicts: UseStateHandle<Status<Rc<Vec<FileConflict>>, ConflictAnalysisError>>) {
+        spawn_local(async move {
+            match tauri::invoke("get_file_conflicts", &Null).await.unwrap() {
+                MMResult::Ok(file_conflicts) => conflicts.set(Status::Loaded(file_conflicts)),
+                MMResult::Err(error) => conflicts.set(Status::Error(error)),
+            }
+        });
+    }
+
+    pub fn set_conflict_resolution(file_path: String, mod_index: Option<usize>,
+                                   conflicts: UseStateHandle<Status<Rc<Vec<FileConflict>>, ConflictAnalysisError>>) {
+        #[derive(Serialize)]
+        struct Args { file_path: String, mod_index: Option<usize> }
+        spawn_local(async move {
+            let _: () = tauri::invoke("set_conflict_resolution", &Args { file_path, mod_index }).await.unwrap();
+            ModManager::get_file_conflicts(conflicts);
+        });
+    }
+
     pub fn set_mod_enabled_state(index: usize, enabled: bool) {
         #[derive(Serialize)]
         struct Args { index: usize, enabled: bool }
@@ -119,22 +257,67 @@ impl ModManager {
         });
     }
 
+    pub fn set_mod_notes(index: usize, notes: String) {
+        #[derive(Serialize)]
+        struct Args { index: usize, notes: String }
+        spawn_local(async move {
+            let _: () = tauri::invoke("set_mod_notes", &Args { index, notes }).await.unwrap();
+        });
+    }
+
+    pub fn move_mods(start: usize, count: usize, position: usize, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
+        #[derive(Serialize)]
+        struct Args { start: usize, count: usize, position: usize }
+        spawn_local(async move {
+            let _: () = tauri::invoke("move_mods", &Args { start, count, position }).await.unwrap();
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn get_installed_game_mods(installed_game_mods: UseStateHandle<Rc<Vec<InstalledGameMod>>>) {
+        spawn_local(async move {
+            installed_game_mods.set(Rc::new(tauri::invoke("get_installed_game_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn import_game_mods(mode: GameModsImportMode, installed_game_mods: UseStateHandle<Rc<Vec<InstalledGameMod>>>,
+                            import_error: UseStateHandle<Option<GameModsImportError>>, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
+        #[derive(Serialize)]
+        struct Args { mode: GameModsImportMode }
+        spawn_local(async move {
+            match tauri::invoke("import_game_mods", &Args { mode }).await.unwrap() {
+                MMResult::Ok(()) => installed_game_mods.set(Rc::new(Vec::new())),
+                MMResult::Err(error) => import_error.set(Some(error)),
+            }
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn dismiss_game_mods_import(installed_game_mods: UseStateHandle<Rc<Vec<InstalledGameMod>>>) {
+        spawn_local(async move {
+            let _: () = tauri::invoke("dismiss_game_mods_import", &Null).await.unwrap();
+            installed_game_mods.set(Rc::new(Vec::new()));
+        });
+    }
+
     pub fn get_profiles(profiles: UseStateHandle<Status<Profiles>>) {
+        spawn_local(async move {
+            profiles.set(Status::Loaded(Self::fetch_profiles().await));
+        });
+    }
+
+    async fn fetch_profiles() -> Profiles {
         #[wasm_bindgen]
         extern "C" {
             #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "tauri"])]
             pub async fn invoke(cmd: &str, args: JsValue) -> JsValue;
         }
-        spawn_local(async move {
-            // Cannot use tauri sys because of int key hashmap weirdness
-            let fetched_profiles = invoke("get_profiles", JsValue::null()).await;
+        // Cannot use tauri sys because of int key hashmap weirdness
+        let fetched_profiles = invoke("get_profiles", JsValue::null()).await;
 
-            // A bit hacky but serde_wasm_bindgen will not deserialize a int key hashmap as it thinks they are strings
-            let fetched_profiles = js_sys::JSON::stringify(&fetched_profiles).unwrap().as_string().unwrap();
-            let fetched_profiles = serde_json::from_str::<Profiles>(&fetched_profiles).unwrap();
-
-            profiles.set(Status::Loaded(fetched_profiles));
-        });
+        // A bit hacky but serde_wasm_bindgen will not deserialize a int key hashmap as it thinks they are strings
+        let fetched_profiles = js_sys::JSON::stringify(&fetched_profiles).unwrap().as_string().unwrap();
+        serde_json::from_str::<Profiles>(&fetched_profiles).unwrap()
     }
 
     pub fn switch_profile(index: usize) {
@@ -145,11 +328,96 @@ impl ModManager {
         });
     }
     
-    pub fn create_profile(name: String) {
+    pub fn create_profile(name: String, profiles: UseStateHandle<Status<Profiles>>, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
         #[derive(Serialize)]
         struct Args { name: String }
         spawn_local(async move {
-            let _: () = tauri::invoke("switch_profile", &Args { name }).await.unwrap();
+            let _: () = tauri::invoke("create_profile", &Args { name }).await.unwrap();
+            profiles.set(Status::Loaded(Self::fetch_profiles().await));
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn rename_profile(index: usize, name: String, profiles: UseStateHandle<Status<Profiles>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize, name: String }
+        spawn_local(async move {
+            let _: () = tauri::invoke("rename_profile", &Args { index, name }).await.unwrap();
+            profiles.set(Status::Loaded(Self::fetch_profiles().await));
+        });
+    }
+
+    pub fn duplicate_profile(index: usize, name: String, profiles: UseStateHandle<Status<Profiles>>, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize, name: String }
+        spawn_local(async move {
+            let _: () = tauri::invoke("duplicate_profile", &Args { index, name }).await.unwrap();
+            profiles.set(Status::Loaded(Self::fetch_profiles().await));
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn add_child_profile(parent: usize, name: String, profiles: UseStateHandle<Status<Profiles>>, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
+        #[derive(Serialize)]
+        struct Args { parent: usize, name: String }
+        spawn_local(async move {
+            let _: () = tauri::invoke("add_child_profile", &Args { parent, name }).await.unwrap();
+            profiles.set(Status::Loaded(Self::fetch_profiles().await));
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn set_profile_parent(profile: usize, parent: Option<usize>, profiles: UseStateHandle<Status<Profiles>>) {
+        #[derive(Serialize)]
+        struct Args { profile: usize, parent: Option<usize> }
+        spawn_local(async move {
+            let _: () = tauri::invoke("set_profile_parent", &Args { profile, parent }).await.unwrap();
+            profiles.set(Status::Loaded(Self::fetch_profiles().await));
+        });
+    }
+
+    pub fn delete_profile(index: usize, profiles: UseStateHandle<Status<Profiles>>, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
+        #[derive(Serialize)]
+        struct Args { index: usize }
+        spawn_local(async move {
+            let _: () = tauri::invoke("delete_profile", &Args { index }).await.unwrap();
+            profiles.set(Status::Loaded(Self::fetch_profiles().await));
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn get_library_mods(library_mods: UseStateHandle<Status<Rc<Vec<LibraryMod>>>>) {
+        spawn_local(async move {
+            library_mods.set(Status::Loaded(tauri::invoke("get_library_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn add_library_mod(path: PathBuf, library_mods: UseStateHandle<Status<Rc<Vec<LibraryMod>>>>,
+                           library_error: UseStateHandle<Option<LibraryError>>, mods: UseStateHandle<Status<Rc<Vec<Mod>>>>) {
+        #[derive(Serialize)]
+        struct Args { path: PathBuf }
+        spawn_local(async move {
+            let result: MMResult<usize, LibraryError> = tauri::invoke("add_library_mod", &Args { path }).await.unwrap();
+            library_error.set(match result {
+                MMResult::Ok(_) => None,
+                MMResult::Err(error) => Some(error),
+            });
+            library_mods.set(Status::Loaded(tauri::invoke("get_library_mods", &Null).await.unwrap()));
+            mods.set(Status::Loaded(tauri::invoke("get_mods", &Null).await.unwrap()));
+        });
+    }
+
+    pub fn delete_library_mod(path: PathBuf, library_mods: UseStateHandle<Status<Rc<Vec<LibraryMod>>>>,
+                              library_error: UseStateHandle<Option<LibraryError>>) {
+        #[derive(Serialize)]
+        struct Args { path: PathBuf }
+        spawn_local(async move {
+            let result: MMResult<(), LibraryError> = tauri::invoke("delete_library_mod", &Args { path }).await.unwrap();
+            library_error.set(match result {
+                MMResult::Ok(()) => None,
+                MMResult::Err(error) => Some(error),
+            });
+            library_mods.set(Status::Loaded(tauri::invoke("get_library_mods", &Null).await.unwrap()));
         });
     }
 }
@@ -10,4 +10,12 @@ impl DomHelper {
             .dyn_into::<web_sys::HtmlInputElement>().ok()?;
         Some(input_node.value())
     }
+
+    pub fn read_select(id: &str) -> Option<String> {
+        let select_node = web_sys::window()?
+            .document()?
+            .get_element_by_id(id)?
+            .dyn_into::<web_sys::HtmlSelectElement>().ok()?;
+        Some(select_node.value())
+    }
 }
\ No newline at end of file
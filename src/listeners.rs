@@ -3,6 +3,9 @@ use std::sync::Arc;
 use yew::UseStateHandle;
 use futures::StreamExt;
 use yew::platform::spawn_local;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, KeyboardEvent};
 use crate::error;
 
 pub fn listen_for_file_drop(dropped_file: UseStateHandle<Option<Arc<PathBuf>>>, on_file_dropped: impl Fn() + 'static) {
@@ -21,4 +24,30 @@ pub fn listen_for_file_drop(dropped_file: UseStateHandle<Option<Arc<PathBuf>>>,
             }
         }
     });
-}
\ No newline at end of file
+}
+
+/// Ctrl+Z undoes and Ctrl+Y or Ctrl+Shift+Z redoes, text fields keep their own undo
+pub fn listen_for_undo(on_undo: impl Fn() + 'static, on_redo: impl Fn() + 'static) {
+    let listener = Closure::<dyn Fn(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+        let in_text_field = event.target().and_then(|target| target.dyn_into::<HtmlInputElement>().ok()).is_some();
+        if !event.ctrl_key() || in_text_field { return }
+
+        match event.key().to_lowercase().as_str() {
+            "z" if event.shift_key() => on_redo(),
+            "z" => on_undo(),
+            "y" => on_redo(),
+            _ => return,
+        }
+        event.prevent_default();
+    });
+
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        error!("Cannot listen for undo without a document");
+        return;
+    };
+    if document.add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref()).is_err() {
+        error!("Could not listen for undo");
+    }
+    // Listens for as long as the app runs
+    listener.forget();
+}
@@ -1,8 +1,9 @@
 use std::ops::Deref;
 use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::Arc;
 use yew::prelude::*;
-use models::{FileEntry, Status};
+use models::{CollisionKind, FileEntry, Mod, ModCollision, Status};
 use crate::bindings::ModManager;
 use crate::components::Spinner;
 use crate::components::Button;
@@ -11,13 +12,26 @@ use crate::components::button::ButtonSize;
 #[derive(Properties, PartialEq)]
 pub struct AddNewModMenuProps {
     pub current_file: UseStateHandle<Option<FileEntry>>,
+    pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
     pub add_mod_menu: UseStateHandle<bool>,
     pub file_explorer_open: UseStateHandle<bool>,
+    pub selected_mod: UseStateHandle<Option<usize>>,
     pub dropped_file: UseStateHandle<Option<Arc<PathBuf>>>,
 }
 #[function_component(AddNewModMenu)]
 pub fn add_new_mod_menu(props: &AddNewModMenuProps) -> Html {
     let details = use_state(|| Status::Loading);
+    let collisions: UseStateHandle<Rc<Vec<ModCollision>>> = use_state(|| Rc::new(Vec::new()));
+
+    {
+        let collisions = collisions.clone();
+        use_effect_with_deps(move |details| {
+            // The backend knows the new mod once its details are read
+            if let Status::Loaded(_) = details.as_ref() {
+                ModManager::get_new_mod_collisions(collisions);
+            }
+        }, details.clone());
+    }
 
     use_effect_with_deps(|(current_file, details, dropped_file)| {
 
@@ -38,12 +52,51 @@ pub fn add_new_mod_menu(props: &AddNewModMenuProps) -> Html {
 
     let add_mod = {
         let file_explorer_open = props.file_explorer_open.clone();
+        let selected_mod = props.selected_mod.clone();
         move |_: MouseEvent| {
-            ModManager::add_mod();
+            ModManager::add_mod(selected_mod.clone());
             file_explorer_open.set(false);
         }
     };
 
+    let new_version = match details.as_ref() {
+        Status::Loaded(details) => details.version.clone(),
+        _ => String::new(),
+    };
+    let updates = collisions.iter()
+        .filter(|collision| collision.kind == CollisionKind::Uuid)
+        .flat_map(|collision| collision.mods.iter())
+        .map(|colliding_mod| {
+            let update = {
+                let file_explorer_open = props.file_explorer_open.clone();
+                let selected_mod = props.selected_mod.clone();
+                let index = colliding_mod.index;
+                move |_: MouseEvent| {
+                    ModManager::update_with_current_mod(index, selected_mod.clone());
+                    file_explorer_open.set(false);
+                }
+            };
+            let old_version = match props.mods.as_ref() {
+                Status::Loaded(mods) => mods.get(colliding_mod.index).map(|installed_mod| installed_mod.version.clone()).unwrap_or_default(),
+                _ => String::new(),
+            };
+            html! {
+                <Button onclick={update} size={ButtonSize::Big} style="width: max-content">
+                    {format!("Update {} {old_version} → {new_version}", colliding_mod.name)}
+                </Button>
+            }
+        })
+        .collect::<Html>();
+
+    let collisions_html = collisions.iter().map(|collision| {
+        let names = collision.mods.iter().map(|colliding_mod| colliding_mod.name.as_str()).collect::<Vec<&str>>().join(", ");
+        let description = match collision.kind {
+            CollisionKind::Uuid => format!("Has the same UUID as {names}, it is likely an update of it"),
+            CollisionKind::Folder => format!("Uses the same folder as {names}, the game will only load one of them"),
+        };
+        html! { <div style="margin-top: 1em;color: var(--conflict)">{description}</div> }
+    }).collect::<Html>();
+
     match details.as_ref() {
         Status::Loading => html! {
             <div style="margin: auto">
@@ -55,9 +108,17 @@ pub fn add_new_mod_menu(props: &AddNewModMenuProps) -> Html {
             <div style="margin: auto;text-align: center">
                 <div style="font-size: 2.5em">{&details.name}</div>
                 <div>{&details.description}</div>
+                {collisions_html}
                 <div style="margin-top: 2em;display: flex;justify-content: center">
                     <Button onclick={close_mod_menu} size={ButtonSize::Big} style="width: min-content">{"Back"}</Button>
-                    <Button onclick={add_mod} size={ButtonSize::Big} style="width: min-content">{"Add mod"}</Button>
+                    {updates}
+                    <Button onclick={add_mod} size={ButtonSize::Big} style="width: max-content">
+                        if collisions.is_empty() {
+                            {"Add mod"}
+                        } else {
+                            {"Add Anyway"}
+                        }
+                    </Button>
                 </div>
             </div>
         },
@@ -1,7 +1,8 @@
 use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::Arc;
 use yew::prelude::*;
-use models::FileEntry;
+use models::{FileEntry, Mod, Status};
 use crate::bindings::FileBrowser;
 use location::FileExplorerLocation;
 use files::Files;
@@ -18,6 +19,7 @@ mod file_nav;
 
 #[derive(Properties, PartialEq)]
 pub struct FileExplorerProps {
+    pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
     pub file_explorer_open: UseStateHandle<bool>,
     pub selected_mod: UseStateHandle<Option<usize>>,
     pub dropped_file: UseStateHandle<Option<Arc<PathBuf>>>,
@@ -47,7 +49,9 @@ pub fn file_explorer(props: &FileExplorerProps) -> Html {
             <AddNewModMenu
                 current_file={current_file.clone()}
                 add_mod_menu={add_mod_menu.clone()}
+                mods={props.mods.clone()}
                 file_explorer_open={props.file_explorer_open.clone()}
+                selected_mod={props.selected_mod.clone()}
                 dropped_file={props.dropped_file.clone()} />
         }
     } else {
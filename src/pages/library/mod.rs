@@ -0,0 +1,148 @@
+use std::rc::Rc;
+use yew::prelude::*;
+use models::{LibraryError, LibraryMod, Mod, Status};
+use crate::bindings::ModManager;
+use crate::components::{Button, Spinner};
+use crate::components::button::ButtonSize;
+
+#[derive(Properties, PartialEq)]
+pub struct LibraryProps {
+    pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
+    pub library_open: UseStateHandle<bool>,
+}
+#[function_component(Library)]
+pub fn library(props: &LibraryProps) -> Html {
+    let library_mods: UseStateHandle<Status<Rc<Vec<LibraryMod>>>> = use_state(|| Status::Loading);
+    let library_error: UseStateHandle<Option<LibraryError>> = use_state(|| None);
+
+    // Switching profiles changes which mods are in use
+    {
+        let library_mods = library_mods.clone();
+        use_effect_with_deps(move |_| {
+            ModManager::get_library_mods(library_mods);
+        }, props.mods.clone());
+    }
+
+    let close = {
+        let library_open = props.library_open.clone();
+        move |_: MouseEvent| {
+            library_open.set(false);
+        }
+    };
+
+    let Status::Loaded(library_mods_ref) = library_mods.as_ref() else {
+        return html! {
+            <div class="library">
+                <Spinner />
+            </div>
+        };
+    };
+
+    html! {
+        <div class="library">
+            <div style="font-size: 1.3em;text-align: center">{"Library"}</div>
+            <div class="library-mods">
+                if library_mods_ref.is_empty() {
+                    <div class="no-mods-found">{"No Mods Found"}</div>
+                }
+                { library_mods_ref.iter().map(|library_mod| html! {
+                    <LibraryModElement
+                        library_mod={library_mod.clone()}
+                        library_mods={library_mods.clone()}
+                        library_error={library_error.clone()}
+                        mods={props.mods.clone()} />
+                }).collect::<Html>() }
+            </div>
+            if let Some(error) = library_error.as_ref() {
+                <div class="library-error">{match error {
+                    LibraryError::NotInLibrary => "The mod is no longer in the library",
+                    LibraryError::ModInUse => "The mod is used by a profile",
+                    LibraryError::CannotDeleteMod => "Could not delete the mod",
+                }}</div>
+            }
+            <div class="library-options">
+                <Button onclick={close} size={ButtonSize::Big} style="width: max-content">{"Return to mod list"}</Button>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct LibraryModElementProps {
+    pub library_mod: LibraryMod,
+    pub library_mods: UseStateHandle<Status<Rc<Vec<LibraryMod>>>>,
+    pub library_error: UseStateHandle<Option<LibraryError>>,
+    pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
+}
+#[function_component(LibraryModElement)]
+pub fn library_mod_element(props: &LibraryModElementProps) -> Html {
+    let library_mod = &props.library_mod;
+
+    let add = {
+        let path = library_mod.path.clone();
+        let library_mods = props.library_mods.clone();
+        let library_error = props.library_error.clone();
+        let mods = props.mods.clone();
+        move |_: MouseEvent| {
+            ModManager::add_library_mod(path.clone(), library_mods.clone(), library_error.clone(), mods.clone());
+        }
+    };
+
+    let delete = {
+        let path = library_mod.path.clone();
+        let library_mods = props.library_mods.clone();
+        let library_error = props.library_error.clone();
+        move |_: MouseEvent| {
+            ModManager::delete_library_mod(path.clone(), library_mods.clone(), library_error.clone());
+        }
+    };
+
+    html! {
+        <div class="library-mod">
+            <div class="library-mod-info">
+                <div>
+                    {&library_mod.name}
+                    if !library_mod.version.is_empty() {
+                        <span class="library-mod-details">{format!(" {}", library_mod.version)}</span>
+                    }
+                </div>
+                if !library_mod.description.is_empty() {
+                    <div class="library-mod-details">{&library_mod.description}</div>
+                }
+                <div class="library-mod-details">
+                    {format_size(library_mod.size)}
+                    if let Some(source) = library_mod.source.as_ref() {
+                        {format!(", from {}", source.to_string_lossy())}
+                    }
+                </div>
+                <div class="library-mod-details">
+                    if library_mod.profiles.is_empty() {
+                        {"Not used by any profile"}
+                    } else {
+                        {format!("Used by {}", library_mod.profiles.join(", "))}
+                    }
+                </div>
+                if !library_mod.retained_by.is_empty() {
+                    <div class="library-mod-details">{format!("Kept for rollback by {}", library_mod.retained_by.join(", "))}</div>
+                }
+            </div>
+            <div class="library-mod-actions">
+                <Button onclick={add} disabled={library_mod.in_current_profile}>{"Add to profile"}</Button>
+                // Mods stay in the library once no profile uses or retains them, only then can they be deleted
+                if !library_mod.in_use {
+                    <Button onclick={delete}>{"Delete"}</Button>
+                }
+            </div>
+        </div>
+    }
+}
+
+fn format_size(size: u64) -> String {
+    #[allow(clippy::cast_precision_loss)]
+    let size = size as f64;
+    if size >= 1024.0 * 1024.0 {
+        format!("{:.1} MiB", size / 1024.0 / 1024.0)
+    } else {
+        format!("{:.1} KiB", size / 1024.0)
+    }
+}
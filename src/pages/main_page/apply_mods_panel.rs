@@ -1,5 +1,6 @@
-
+use std::rc::Rc;
 use yew::prelude::*;
+use models::{ApplyWarnings, ConflictAnalysisError, FileConflict, LoadOrderSortError, Mod, Status};
 use crate::bindings::ModManager;
 use crate::components::Button;
 use crate::components::Spinner;
@@ -8,27 +9,65 @@ use crate::components::button::ButtonSize;
 
 
 #[derive(Properties, PartialEq)]
-pub struct ApplyModsPanelProps;
+pub struct ApplyModsPanelProps {
+    pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
+    pub selected_mod: UseStateHandle<Option<usize>>,
+    pub conflicts: UseStateHandle<Status<Rc<Vec<FileConflict>>, ConflictAnalysisError>>,
+    pub reviewing_conflicts: UseStateHandle<bool>,
+    pub apply_warnings: UseStateHandle<Option<Rc<ApplyWarnings>>>,
+}
 #[function_component(ApplyModsPanel)]
-pub fn apply_mods_panel(_props: &ApplyModsPanelProps) -> Html {
+pub fn apply_mods_panel(props: &ApplyModsPanelProps) -> Html {
     let applying = use_state(|| false);
+    let sort_error: UseStateHandle<Option<LoadOrderSortError>> = use_state(|| None);
+
+    let has_conflicts = matches!(props.conflicts.as_ref(), Status::Loaded(conflicts) if !conflicts.is_empty());
 
     let apply = {
         let applying = applying.clone();
+        let reviewing_conflicts = props.reviewing_conflicts.clone();
+        let apply_warnings = props.apply_warnings.clone();
         move |_: MouseEvent| {
-            ModManager::apply(applying.clone());
+            // Conflicts are shown to the user before the first apply
+            if has_conflicts && !*reviewing_conflicts && apply_warnings.is_none() {
+                reviewing_conflicts.set(true);
+                return;
+            }
+            reviewing_conflicts.set(false);
+            // Warnings are returned by the first apply and ignored once the user has seen them
+            ModManager::apply(applying.clone(), apply_warnings.is_some(), apply_warnings.clone());
+        }
+    };
+
+    let sort = {
+        let mods = props.mods.clone();
+        let selected_mod = props.selected_mod.clone();
+        let sort_error = sort_error.clone();
+        move |_: MouseEvent| {
+            selected_mod.set(None);
+            ModManager::sort_mods(mods.clone(), sort_error.clone());
         }
     };
 
     html! {
         <div class="apply-mods-panel">
+            if let Some(LoadOrderSortError::DependencyCycle { mods }) = sort_error.as_ref() {
+                <div class="sort-error">{format!("Cannot sort, dependencies form a cycle: {}", mods.join(" → "))}</div>
+            } else if let Some(LoadOrderSortError::ContradictoryRules { mods }) = sort_error.as_ref() {
+                <div class="sort-error">{format!("Cannot sort, load order rules contradict each other: {}", mods.join(" → "))}</div>
+            }
+            <Button size={ButtonSize::Big} class="sort-mods-button" onclick={sort} disabled={*applying}>
+                {"Sort"}
+            </Button>
             <Button size={ButtonSize::Big} class="apply-mods-button" onclick={apply} disabled={*applying}>
                 if *applying {
                     <Spinner size={SpinnerSize::Small} />
+                } else if *props.reviewing_conflicts || props.apply_warnings.is_some() {
+                    {"Apply Anyway"}
                 } else {
                     {"Apply Mods"}
                 }
             </Button>
         </div>
     }
-}
\ No newline at end of file
+}
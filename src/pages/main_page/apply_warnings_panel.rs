@@ -0,0 +1,77 @@
+use std::rc::Rc;
+use yew::prelude::*;
+use models::{ApplyWarnings, CollisionKind, DependencyProblem, DependencyProblemKind, MasterlistFinding, MasterlistFindingKind};
+use crate::components::Button;
+use crate::components::button::ButtonSize;
+
+#[derive(Properties, PartialEq)]
+pub struct ApplyWarningsPanelProps {
+    pub apply_warnings: UseStateHandle<Option<Rc<ApplyWarnings>>>,
+}
+#[function_component(ApplyWarningsPanel)]
+pub fn apply_warnings_panel(props: &ApplyWarningsPanelProps) -> Html {
+    let cancel = {
+        let apply_warnings = props.apply_warnings.clone();
+        move |_: MouseEvent| {
+            apply_warnings.set(None);
+        }
+    };
+
+    let Some(warnings) = props.apply_warnings.as_ref() else { return html! {} };
+
+    html! {
+        <div class="apply-warnings-panel">
+            <div style="font-size: 1.3em;text-align: center">{"Problems with this profile"}</div>
+            <div style="font-size: 0.8em;text-align: center">{"The game may refuse to load some mods or misbehave, apply anyway to ignore this"}</div>
+            <div class="apply-warning-list">
+                { warnings.collisions.iter().map(|collision| html! {
+                    <div class="apply-warning-mod">
+                        <div>{collision.mods.iter().map(|colliding_mod| colliding_mod.name.as_str()).collect::<Vec<&str>>().join(", ")}</div>
+                        <div class="apply-warning">{match collision.kind {
+                            CollisionKind::Uuid => format!("Share the UUID {}, the game will only load one of them", collision.value),
+                            CollisionKind::Folder => format!("Share the folder {}, the game will only load one of them", collision.value),
+                        }}</div>
+                    </div>
+                }).collect::<Html>() }
+                { warnings.dependency_problems.iter().map(|mod_problems| html! {
+                    <div class="apply-warning-mod">
+                        <div>{&mod_problems.dependent_mod.name}</div>
+                        { mod_problems.problems.iter().map(|problem| html! {
+                            <div class="apply-warning">{describe_problem(problem)}</div>
+                        }).collect::<Html>() }
+                    </div>
+                }).collect::<Html>() }
+                { warnings.masterlist_findings.iter().map(|finding| html! {
+                    <div class="apply-warning-mod">
+                        <div>{&finding.subject.name}</div>
+                        <div class="apply-warning">{describe_finding(finding)}</div>
+                    </div>
+                }).collect::<Html>() }
+            </div>
+            <div class="apply-warnings-options">
+                <Button onclick={cancel} size={ButtonSize::Big} style="width: min-content">{"Cancel"}</Button>
+            </div>
+        </div>
+    }
+}
+
+fn describe_problem(problem: &DependencyProblem) -> String {
+    match &problem.kind {
+        DependencyProblemKind::NotInstalled => format!("Requires {} which is not installed", problem.name),
+        DependencyProblemKind::Disabled => format!("Requires {} which is disabled", problem.name),
+        DependencyProblemKind::VersionTooOld { installed_version, required_version } =>
+            format!("Requires {} {required_version} or newer, {installed_version} is installed", problem.name),
+    }
+}
+
+pub fn describe_finding(finding: &MasterlistFinding) -> String {
+    let description = match &finding.kind {
+        MasterlistFindingKind::Incompatible { other } => format!("Incompatible with {}", other.name),
+        MasterlistFindingKind::WrongOrder { should_load_after } => format!("Should load after {}", should_load_after.name),
+        MasterlistFindingKind::Message => return finding.note.clone().unwrap_or_default(),
+    };
+    match &finding.note {
+        Some(note) => format!("{description}: {note}"),
+        None => description,
+    }
+}
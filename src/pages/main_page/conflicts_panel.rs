@@ -0,0 +1,90 @@
+use std::rc::Rc;
+use yew::prelude::*;
+use models::{ConflictAnalysisError, FileConflict, Status};
+use crate::bindings::ModManager;
+use crate::components::Button;
+use crate::components::button::ButtonSize;
+
+#[derive(Properties, PartialEq)]
+pub struct ConflictsPanelProps {
+    pub conflicts: UseStateHandle<Status<Rc<Vec<FileConflict>>, ConflictAnalysisError>>,
+    pub reviewing_conflicts: UseStateHandle<bool>,
+}
+#[function_component(ConflictsPanel)]
+pub fn conflicts_panel(props: &ConflictsPanelProps) -> Html {
+    let cancel = {
+        let reviewing_conflicts = props.reviewing_conflicts.clone();
+        move |_: MouseEvent| {
+            reviewing_conflicts.set(false);
+        }
+    };
+
+    let conflicts_html = match props.conflicts.as_ref() {
+        Status::Loaded(conflicts) => conflicts.iter().map(|conflict| html! {
+            <ConflictElement conflict={conflict.clone()} conflicts={props.conflicts.clone()} />
+        }).collect::<Html>(),
+        Status::Loading => html! {},
+        Status::Error(error) => html! {
+            <div>{format!("Could not check for conflicts: {error:?}")}</div>
+        },
+    };
+
+    let conflict_count = match props.conflicts.as_ref() {
+        Status::Loaded(conflicts) => conflicts.len(),
+        _ => 0,
+    };
+
+    html! {
+        <div class="conflicts-panel">
+            <div style="font-size: 1.3em;text-align: center">{format!("{conflict_count} conflicting files")}</div>
+            <div style="font-size: 0.8em;text-align: center">{"Mods lower in the list override the files of mods above them, pick a mod to override this"}</div>
+            <div class="conflict-list">{conflicts_html}</div>
+            <div class="conflicts-options">
+                <Button onclick={cancel} size={ButtonSize::Big} style="width: min-content">{"Cancel"}</Button>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ConflictElementProps {
+    pub conflict: FileConflict,
+    pub conflicts: UseStateHandle<Status<Rc<Vec<FileConflict>>, ConflictAnalysisError>>,
+}
+#[function_component(ConflictElement)]
+fn conflict_element(props: &ConflictElementProps) -> Html {
+    let providers_html = props.conflict.providers.iter().map(|provider| {
+        let selected = provider.index == props.conflict.winner.index;
+        let onclick = {
+            let file_path = props.conflict.path.clone();
+            let conflicts = props.conflicts.clone();
+            let mod_index = provider.index;
+            move |_: MouseEvent| {
+                ModManager::set_conflict_resolution(file_path.clone(), Some(mod_index), conflicts.clone());
+            }
+        };
+        html! {
+            <Button class="conflict-provider" size={ButtonSize::Thin} selected={selected} onclick={onclick}>
+                {&provider.name}
+            </Button>
+        }
+    }).collect::<Html>();
+
+    let use_load_order = {
+        let file_path = props.conflict.path.clone();
+        let conflicts = props.conflicts.clone();
+        move |_: MouseEvent| {
+            ModManager::set_conflict_resolution(file_path.clone(), None, conflicts.clone());
+        }
+    };
+
+    html! {
+        <div class="conflict">
+            <div class="conflict-path">{&props.conflict.path}</div>
+            {providers_html}
+            if props.conflict.resolved {
+                <Button class="conflict-provider" size={ButtonSize::Thin} onclick={use_load_order}>{"Use load order"}</Button>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,68 @@
+use std::rc::Rc;
+use yew::prelude::*;
+use models::{GameModsImportError, GameModsImportMode, InstalledGameMod, Mod, Status};
+use crate::bindings::ModManager;
+use crate::components::Button;
+use crate::components::button::ButtonSize;
+
+#[derive(Properties, PartialEq)]
+pub struct GameModsImportPanelProps {
+    pub installed_game_mods: UseStateHandle<Rc<Vec<InstalledGameMod>>>,
+    pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
+}
+#[function_component(GameModsImportPanel)]
+pub fn game_mods_import_panel(props: &GameModsImportPanelProps) -> Html {
+    let import_error: UseStateHandle<Option<GameModsImportError>> = use_state(|| None);
+
+    let import = |mode: GameModsImportMode| {
+        let installed_game_mods = props.installed_game_mods.clone();
+        let import_error = import_error.clone();
+        let mods = props.mods.clone();
+        move |_: MouseEvent| {
+            ModManager::import_game_mods(mode, installed_game_mods.clone(), import_error.clone(), mods.clone());
+        }
+    };
+
+    let dismiss = {
+        let installed_game_mods = props.installed_game_mods.clone();
+        move |_: MouseEvent| {
+            ModManager::dismiss_game_mods_import(installed_game_mods.clone());
+        }
+    };
+
+    html! {
+        <div class="game-mods-import-panel">
+            <div style="font-size: 1.3em;text-align: center">{"Installed mods found"}</div>
+            <div style="font-size: 0.8em;text-align: center">
+                {"These mods were installed without the manager, applying a profile replaces their load order. Import them into a new profile to keep them"}
+            </div>
+            <div class="game-mod-list">
+                { props.installed_game_mods.iter().map(|game_mod| html! {
+                    <div class="game-mod">
+                        <div>{&game_mod.name}</div>
+                        <div class="game-mod-details">
+                            {&game_mod.file_name}
+                            if !game_mod.enabled {
+                                {", inactive"}
+                            }
+                        </div>
+                    </div>
+                }).collect::<Html>() }
+            </div>
+            if let Some(GameModsImportError::CannotStoreMod { mod_name }) = import_error.as_ref() {
+                <div class="game-mods-import-error">{format!("Could not store {mod_name}, the mods before it were imported")}</div>
+            }
+            <div class="game-mods-import-options">
+                <Button onclick={import(GameModsImportMode::Move)} size={ButtonSize::Big} style="width: min-content">{"Move"}</Button>
+                <Button onclick={import(GameModsImportMode::Copy)} size={ButtonSize::Big} style="width: min-content">{"Copy"}</Button>
+                <Button onclick={dismiss} size={ButtonSize::Big} style="width: min-content">
+                    if import_error.is_some() {
+                        {"Close"}
+                    } else {
+                        {"Ignore"}
+                    }
+                </Button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,103 @@
+use std::rc::Rc;
+use yew::prelude::*;
+use models::{LoadOrderRule, Mod};
+use crate::bindings::ModManager;
+use crate::components::Button;
+use crate::components::button::ButtonSize;
+use crate::helpers::DomHelper;
+
+const RULE_TARGET_ID: &str = "load-order-rule-target";
+
+#[derive(Properties, PartialEq)]
+pub struct LoadOrderRulesProps {
+    pub mods: Rc<Vec<Mod>>,
+    pub index: usize,
+}
+#[function_component(LoadOrderRules)]
+pub fn load_order_rules(props: &LoadOrderRulesProps) -> Html {
+    let rules: UseStateHandle<Rc<Vec<LoadOrderRule>>> = use_state(|| Rc::new(Vec::new()));
+
+    {
+        let rules = rules.clone();
+        use_effect_with_deps(move |index| {
+            ModManager::get_load_order_rules(*index, rules);
+        }, props.index);
+    }
+
+    let mod_name = |uuid: &str| props.mods.iter()
+        .find(|mod_info| mod_info.uuid.as_deref().is_some_and(|mod_uuid| mod_uuid.eq_ignore_ascii_case(uuid)))
+        .map_or_else(|| uuid.to_string(), |mod_info| mod_info.name.clone());
+
+    let set_rules = {
+        let rules = rules.clone();
+        let index = props.index;
+        move |new_rules: Vec<LoadOrderRule>| ModManager::set_load_order_rules(index, new_rules, rules.clone())
+    };
+
+    let add_rule = |make_rule: fn() -> Option<LoadOrderRule>| {
+        let rules = rules.clone();
+        let set_rules = set_rules.clone();
+        move |_: MouseEvent| {
+            let Some(rule) = make_rule() else { return };
+            if rules.contains(&rule) { return }
+
+            let mut new_rules = (**rules).clone();
+            // A mod is only pinned to one end
+            if matches!(rule, LoadOrderRule::PinTop | LoadOrderRule::PinBottom) {
+                new_rules.retain(|rule| !matches!(rule, LoadOrderRule::PinTop | LoadOrderRule::PinBottom));
+            }
+            new_rules.push(rule);
+            set_rules(new_rules);
+        }
+    };
+
+    let rules_html = rules.iter().enumerate().map(|(rule_index, rule)| {
+        let description = match rule {
+            LoadOrderRule::LoadAfter { uuid } => format!("Load after {}", mod_name(uuid)),
+            LoadOrderRule::LoadBefore { uuid } => format!("Load before {}", mod_name(uuid)),
+            LoadOrderRule::PinTop => String::from("Pinned to the top"),
+            LoadOrderRule::PinBottom => String::from("Pinned to the bottom"),
+        };
+        let remove_rule = {
+            let rules = rules.clone();
+            let set_rules = set_rules.clone();
+            move |_: MouseEvent| {
+                let mut new_rules = (**rules).clone();
+                new_rules.remove(rule_index);
+                set_rules(new_rules);
+            }
+        };
+        html! {
+            <div class="load-order-rule">
+                <div>{description}</div>
+                <Button onclick={remove_rule} size={ButtonSize::Thin}>{"Remove"}</Button>
+            </div>
+        }
+    }).collect::<Html>();
+
+    let targets = props.mods.iter().enumerate()
+        .filter(|(index, _)| *index != props.index)
+        .filter_map(|(_, mod_info)| Some((mod_info.uuid.clone()?, mod_info.name.clone())))
+        .collect::<Vec<(String, String)>>();
+
+    html! {
+        <div class="load-order-rules">
+            {rules_html}
+            <div class="load-order-rule-options">
+                <Button onclick={add_rule(|| Some(LoadOrderRule::PinTop))} size={ButtonSize::Thin}>{"Pin Top"}</Button>
+                <Button onclick={add_rule(|| Some(LoadOrderRule::PinBottom))} size={ButtonSize::Thin}>{"Pin Bottom"}</Button>
+            </div>
+            if !targets.is_empty() {
+                <div class="load-order-rule-options">
+                    <select id={RULE_TARGET_ID} class="load-order-rule-target">
+                        { targets.iter().map(|(uuid, name)| html! {
+                            <option value={uuid.clone()}>{name}</option>
+                        }).collect::<Html>() }
+                    </select>
+                    <Button onclick={add_rule(|| Some(LoadOrderRule::LoadAfter { uuid: DomHelper::read_select(RULE_TARGET_ID)? }))} size={ButtonSize::Thin}>{"Load After"}</Button>
+                    <Button onclick={add_rule(|| Some(LoadOrderRule::LoadBefore { uuid: DomHelper::read_select(RULE_TARGET_ID)? }))} size={ButtonSize::Thin}>{"Load Before"}</Button>
+                </div>
+            }
+        </div>
+    }
+}
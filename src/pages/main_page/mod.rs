@@ -1,13 +1,21 @@
 use std::rc::Rc;
 use yew::prelude::*;
-use models::{Mod, Status};
+use models::{ApplyWarnings, InstalledGameMod, ConflictAnalysisError, FileConflict, Mod, Status};
 use mod_list::ModList;
 use selected_mod_panel::SelectedMod;
 use apply_mods_panel::ApplyModsPanel;
+use conflicts_panel::ConflictsPanel;
+use apply_warnings_panel::ApplyWarningsPanel;
+use game_mods_import_panel::GameModsImportPanel;
+use crate::bindings::ModManager;
 
 mod mod_list;
 mod selected_mod_panel;
 mod apply_mods_panel;
+mod conflicts_panel;
+mod apply_warnings_panel;
+mod load_order_rules;
+mod game_mods_import_panel;
 
 
 #[derive(Properties, PartialEq)]
@@ -20,18 +28,57 @@ pub struct MainPageProps {
 }
 #[function_component(MainPage)]
 pub fn main_page(props: &MainPageProps) -> Html {
+    let conflicts: UseStateHandle<Status<Rc<Vec<FileConflict>>, ConflictAnalysisError>> = use_state(|| Status::Loading);
+    let reviewing_conflicts = use_state(|| false);
+    let apply_warnings: UseStateHandle<Option<Rc<ApplyWarnings>>> = use_state(|| None);
+    let installed_game_mods: UseStateHandle<Rc<Vec<InstalledGameMod>>> = use_state(|| Rc::new(Vec::new()));
+
+    {
+        let installed_game_mods = installed_game_mods.clone();
+        use_effect_with_deps(move |_| {
+            ModManager::get_installed_game_mods(installed_game_mods);
+        }, ());
+    }
+
+    {
+        let conflicts = conflicts.clone();
+        use_effect_with_deps(move |mods| {
+            if let Status::Loaded(_) = mods.as_ref() {
+                ModManager::get_file_conflicts(conflicts);
+            }
+        }, props.mods.clone());
+    }
+
     html! {
         <div class="main-page">
             <ModList
                 mods={props.mods.clone()}
+                conflicts={conflicts.clone()}
                 selected_mod={props.selected_mod.clone()}
                 file_explorer_open={props.file_explorer_open.clone()}
                 profile_open={props.profile_open.clone()}
                 profile_create_new={props.profile_create_new.clone()} />
-            <SelectedMod
+            if !installed_game_mods.is_empty() {
+                <GameModsImportPanel
+                    installed_game_mods={installed_game_mods.clone()}
+                    mods={props.mods.clone()} />
+            } else if apply_warnings.is_some() {
+                <ApplyWarningsPanel apply_warnings={apply_warnings.clone()} />
+            } else if *reviewing_conflicts {
+                <ConflictsPanel
+                    conflicts={conflicts.clone()}
+                    reviewing_conflicts={reviewing_conflicts.clone()} />
+            } else {
+                <SelectedMod
+                    mods={props.mods.clone()}
+                    selected_mod={props.selected_mod.clone()} />
+            }
+            <ApplyModsPanel
                 mods={props.mods.clone()}
-                selected_mod={props.selected_mod.clone()} />
-            <ApplyModsPanel />
+                selected_mod={props.selected_mod.clone()}
+                conflicts={conflicts.clone()}
+                reviewing_conflicts={reviewing_conflicts.clone()}
+                apply_warnings={apply_warnings.clone()} />
         </div>
     }
 }
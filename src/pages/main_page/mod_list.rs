@@ -1,6 +1,7 @@
+use std::collections::HashSet;
 use std::rc::Rc;
 use yew::prelude::*;
-use models::{Mod, Status};
+use models::{ConflictAnalysisError, FileConflict, Mod, Status};
 use crate::bindings::ModManager;
 use crate::components::Button;
 use crate::components::button::ButtonSize;
@@ -9,6 +10,7 @@ use crate::components::Spinner;
 #[derive(Properties, PartialEq)]
 pub struct ModListProps {
     pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
+    pub conflicts: UseStateHandle<Status<Rc<Vec<FileConflict>>, ConflictAnalysisError>>,
     pub selected_mod: UseStateHandle<Option<usize>>,
     pub file_explorer_open: UseStateHandle<bool>,
     pub profile_open: UseStateHandle<bool>,
@@ -20,6 +22,8 @@ pub fn mod_list(props: &ModListProps) -> Html {
         ModManager::get_mods(mods.clone());
     }, props.mods.clone());
 
+    let dragged_mod = use_state(|| None);
+    let drop_target = use_state(|| None);
 
     if let Status::Loaded(mods) = props.mods.as_ref() {
         let open_file_explorer = {
@@ -35,14 +39,37 @@ pub fn mod_list(props: &ModListProps) -> Html {
             }
         };
 
+        let conflicting_mods = match props.conflicts.as_ref() {
+            Status::Loaded(conflicts) => conflicts.iter()
+                .flat_map(|conflict| conflict.providers.iter().map(|provider| provider.index))
+                .collect::<HashSet<usize>>(),
+            _ => HashSet::new(),
+        };
+
+        let stop_dragging = {
+            let dragged_mod = dragged_mod.clone();
+            let drop_target = drop_target.clone();
+            move |_: MouseEvent| {
+                dragged_mod.set(None);
+                drop_target.set(None);
+            }
+        };
+
         html! {
             <>
-                <div class="mod-list">
+                <div class={classes!("mod-list", dragged_mod.is_some().then_some("mod-list-dragging"))} onmouseleave={stop_dragging.clone()} onmouseup={stop_dragging}>
                     if mods.len() == 0 {
                         <div class="no-mods-found">{"No Mods Found"}</div>
                     } else {
                         <div>{ mods.iter().enumerate().map(|(index, mod_info)| html! {
-                            <ModElement mod_info={(*mod_info).clone()} selected_mod={props.selected_mod.clone()} index={index} />
+                            <ModElement
+                                mod_info={(*mod_info).clone()}
+                                mods={props.mods.clone()}
+                                selected_mod={props.selected_mod.clone()}
+                                dragged_mod={dragged_mod.clone()}
+                                drop_target={drop_target.clone()}
+                                index={index}
+                                conflicting={conflicting_mods.contains(&index)} />
                         }).collect::<Html>() }</div>
                     }
                 </div>
@@ -62,8 +89,13 @@ pub fn mod_list(props: &ModListProps) -> Html {
 #[derive(Properties, PartialEq)]
 pub struct ModElementProps {
     pub mod_info: Mod,
+    pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
     pub selected_mod: UseStateHandle<Option<usize>>,
+    pub dragged_mod: UseStateHandle<Option<usize>>,
+    pub drop_target: UseStateHandle<Option<usize>>,
     pub index: usize,
+    #[prop_or_default]
+    pub conflicting: bool,
 }
 #[function_component(ModElement)]
 fn mod_component(props: &ModElementProps) -> Html {
@@ -86,12 +118,67 @@ fn mod_component(props: &ModElementProps) -> Html {
         })
     };
 
-    html! {
+    let start_dragging = {
+        let dragged_mod = props.dragged_mod.clone();
+        let index = props.index;
+        move |event: MouseEvent| {
+            if event.button() == 0 {
+                dragged_mod.set(Some(index));
+            }
+        }
+    };
 
-        <Button class={classes!("mod-element", if props.mod_info.enabled { None } else { Some("make-element-disabled-lite") })} size={ButtonSize::Thin} onclick={onclick} selected={selected}>
-            <div style="font-size: 0.9em;">{props.mod_info.name.clone()}</div>
-            <div style="font-size: 0.7em;transform: translate(0, 0.2em);overflow-x:hidden;text-overflow: ellipsis">{props.mod_info.description.clone()}</div>
-            <div style="font-size: 0.9em">{props.mod_info.version.clone()}</div>
-        </Button>
+    let drag_over = {
+        let dragged_mod = props.dragged_mod.clone();
+        let drop_target = props.drop_target.clone();
+        let index = props.index;
+        move |_: MouseEvent| {
+            if dragged_mod.is_some() {
+                drop_target.set(Some(index));
+            }
+        }
+    };
+
+    // Dropping moves the dragged mod to this position, so it loads before or after this mod depending on the direction
+    let drop = {
+        let mods = props.mods.clone();
+        let selected_mod = props.selected_mod.clone();
+        let dragged_mod = props.dragged_mod.clone();
+        let position = props.index;
+        move |_: MouseEvent| {
+            let Some(index) = *dragged_mod else { return };
+            if index == position { return }
+
+            if let Some(selected_index) = *selected_mod {
+                let selected_index = if selected_index == index {
+                    position
+                } else if index < selected_index && selected_index <= position {
+                    selected_index - 1
+                } else if position <= selected_index && selected_index < index {
+                    selected_index + 1
+                } else {
+                    selected_index
+                };
+                selected_mod.set(Some(selected_index));
+            }
+            ModManager::move_mods(index, 1, position, mods.clone());
+        }
+    };
+
+    let dragging = *props.dragged_mod == Some(props.index);
+    let drop_target = !dragging && props.dragged_mod.is_some() && *props.drop_target == Some(props.index);
+
+    html! {
+        <div
+            class={classes!("mod-drag-handle", dragging.then_some("mod-dragged"), drop_target.then_some("mod-drop-target"))}
+            onmousedown={start_dragging}
+            onmouseenter={drag_over}
+            onmouseup={drop}>
+            <Button class={classes!("mod-element", if props.mod_info.enabled { None } else { Some("make-element-disabled-lite") }, if props.conflicting { Some("mod-element-conflicting") } else { None })} size={ButtonSize::Thin} onclick={onclick} selected={selected}>
+                <div style="font-size: 0.9em;">{props.mod_info.name.clone()}</div>
+                <div style="font-size: 0.7em;transform: translate(0, 0.2em);overflow-x:hidden;text-overflow: ellipsis">{props.mod_info.description.clone()}</div>
+                <div style="font-size: 0.9em">{props.mod_info.version.clone()}</div>
+            </Button>
+        </div>
     }
 }
\ No newline at end of file
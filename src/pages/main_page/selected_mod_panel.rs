@@ -1,9 +1,11 @@
 use std::rc::Rc;
 use yew::prelude::*;
-use models::{Mod, Status};
+use models::{AvailableDependency, DependencySource, MasterlistFinding, Mod, Status};
 use crate::bindings::ModManager;
 use crate::components::Button;
 use crate::components::button::ButtonSize;
+use super::load_order_rules::LoadOrderRules;
+use super::apply_warnings_panel::describe_finding;
 
 #[derive(Properties, PartialEq)]
 pub struct SelectedModProps {
@@ -18,6 +20,34 @@ pub fn selected_mod(props: &SelectedModProps) -> Html {
         mods.get(*selected_mod)
     })(props.selected_mod.as_ref(), props.mods.as_ref());
 
+    let available_dependencies: UseStateHandle<Rc<Vec<AvailableDependency>>> = use_state(|| Rc::new(Vec::new()));
+    {
+        let available_dependencies = available_dependencies.clone();
+        let enabled = selected_mod.map(|selected_mod| selected_mod.enabled);
+        use_effect_with_deps(move |(selected_mod, enabled, _)| {
+            // Dependencies are offered when a mod is added or enabled
+            match (*selected_mod, enabled) {
+                (Some(index), Some(true)) => ModManager::get_available_dependencies(index, available_dependencies),
+                _ => available_dependencies.set(Rc::new(Vec::new())),
+            }
+        }, (*props.selected_mod, enabled, props.mods.clone()));
+    }
+
+    let masterlist_findings: UseStateHandle<Rc<Vec<MasterlistFinding>>> = use_state(|| Rc::new(Vec::new()));
+    {
+        let masterlist_findings = masterlist_findings.clone();
+        use_effect_with_deps(move |_| {
+            ModManager::get_masterlist_findings(masterlist_findings);
+        }, props.mods.clone());
+    }
+
+    let version_retention = use_state(|| 0);
+    {
+        let version_retention = version_retention.clone();
+        use_effect_with_deps(move |_| {
+            ModManager::get_version_retention(version_retention);
+        }, ());
+    }
 
     if let Some(selected_mod) = selected_mod {
         let toggle_enabled = {
@@ -40,11 +70,95 @@ pub fn selected_mod(props: &SelectedModProps) -> Html {
             }
         };
 
+        let save_notes = {
+            let mods = props.mods.clone();
+            let mod_index = props.selected_mod.unwrap();
+            move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                ModManager::set_mod_notes(mod_index, input.value());
+                ModManager::get_mods(mods.clone());
+            }
+        };
+
+        let set_version_retention = {
+            let mods = props.mods.clone();
+            let version_retention = version_retention.clone();
+            move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let Ok(retention) = input.value().parse::<usize>() else { return };
+                ModManager::set_version_retention(retention, version_retention.clone(), mods.clone());
+            }
+        };
+
+        let add_dependencies = {
+            let mods = props.mods.clone();
+            let selected_mod = props.selected_mod.clone();
+            let mod_index = props.selected_mod.unwrap();
+            move |_: MouseEvent| {
+                ModManager::add_dependencies(mod_index, mods.clone(), selected_mod.clone());
+            }
+        };
+
+        let all_in_profile = available_dependencies.iter()
+            .all(|dependency| matches!(dependency.source, DependencySource::Profile { .. }));
+        let dependency_names = available_dependencies.iter()
+            .map(|dependency| dependency.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+
         html! {
             <div class="selected-mod-panel">
                 <div style="font-size: 1.3em;text-align: center">{&selected_mod.name}</div>
                 <div style="font-size: 0.8em;text-align: center">{&selected_mod.description}</div>
                 <div style="font-size: 1em;text-align: center">{&selected_mod.version}</div>
+                <input
+                    key={props.selected_mod.unwrap()}
+                    class="mod-notes"
+                    placeholder="Notes"
+                    value={selected_mod.notes.clone()}
+                    onchange={save_notes} />
+                if !selected_mod.previous_versions.is_empty() {
+                    <div class="previous-versions">
+                        { selected_mod.previous_versions.iter().enumerate().map(|(version_index, version)| {
+                            let rollback = {
+                                let mods = props.mods.clone();
+                                let mod_index = props.selected_mod.unwrap();
+                                move |_: MouseEvent| ModManager::rollback_mod(mod_index, version_index, mods.clone())
+                            };
+                            html! {
+                                <div class="previous-version">
+                                    <div>{version}</div>
+                                    <Button onclick={rollback} size={ButtonSize::Thin}>{"Roll Back"}</Button>
+                                </div>
+                            }
+                        }).collect::<Html>() }
+                        <label class="version-retention">
+                            {"Keep"}
+                            <input type="number" min="0" value={version_retention.to_string()} onchange={set_version_retention} />
+                            {"previous versions"}
+                        </label>
+                    </div>
+                }
+                if let (Some(_), Status::Loaded(mods)) = (&selected_mod.uuid, props.mods.as_ref()) {
+                    <LoadOrderRules mods={mods.clone()} index={props.selected_mod.unwrap()} />
+                }
+                { masterlist_findings.iter()
+                    .filter(|finding| Some(finding.subject.index) == *props.selected_mod)
+                    .map(|finding| html! {
+                        <div class="masterlist-finding">{describe_finding(finding)}</div>
+                    }).collect::<Html>() }
+                if !available_dependencies.is_empty() {
+                    <div class="available-dependencies">
+                        <div>{format!("Needs {dependency_names}")}</div>
+                        <Button onclick={add_dependencies} size={ButtonSize::Thin}>
+                            if all_in_profile {
+                                {"Enable Dependencies"}
+                            } else {
+                                {"Add Dependencies"}
+                            }
+                        </Button>
+                    </div>
+                }
                 <div class="selected-mod-options">
                     <Button onclick={remove_mod} size={ButtonSize::Big} style="width: min-content">{"Remove Mod"}</Button>
                     <Button onclick={toggle_enabled} size={ButtonSize::Big} style="width: min-content">
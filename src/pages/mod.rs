@@ -1,2 +1,3 @@
 pub mod file_explorer;
 pub mod main_page;
+pub mod library;
@@ -2,12 +2,14 @@ use std::rc::Rc;
 use yew::prelude::*;
 use models::{Mod, Status};
 use profiles::Profiles;
+use crate::components::Button;
 
 mod profiles;
 
 #[derive(Properties, PartialEq)]
 pub struct TopBarProps {
     pub file_explorer_open: UseStateHandle<bool>,
+    pub library_open: UseStateHandle<bool>,
     pub selected_mod: UseStateHandle<Option<usize>>,
     pub mods: UseStateHandle<Status<Rc<Vec<Mod>>>>,
     pub profile_open: UseStateHandle<bool>,
@@ -16,6 +18,17 @@ pub struct TopBarProps {
 
 #[function_component(TopBar)]
 pub fn top_bar(props: &TopBarProps) -> Html {
+    let toggle_library = {
+        let library_open = props.library_open.clone();
+        let selected_mod = props.selected_mod.clone();
+        let profile_open = props.profile_open.clone();
+        move |_: MouseEvent| {
+            library_open.set(!*library_open);
+            selected_mod.set(None);
+            profile_open.set(false);
+        }
+    };
+
     html! {
         <div class="top-bar">
             <Profiles selected_mod={props.selected_mod.clone()}
@@ -23,6 +36,9 @@ pub fn top_bar(props: &TopBarProps) -> Html {
                 profile_open={props.profile_open.clone()}
                 profile_create_new={props.profile_create_new.clone()}
                 disabled={*props.file_explorer_open} />
+            <Button onclick={toggle_library} disabled={*props.file_explorer_open} selected={*props.library_open} class="library-toggle">
+                {"Library"}
+            </Button>
         </div>
     }
 }
\ No newline at end of file
@@ -20,10 +20,13 @@ pub struct ProfilesProps {
 #[function_component(Profiles)]
 pub fn profiles(props: &ProfilesProps) -> Html {
     let profiles = use_state(|| Status::Loading);
+    let profile_renaming = use_state(|| false);
+    let profile_deleting = use_state(|| false);
 
-    use_effect_with_deps(|profiles| {
+    // Profiles can also be created by importing mods
+    use_effect_with_deps(|(profiles, _)| {
         ModManager::get_profiles(profiles.clone());
-    }, profiles.clone());
+    }, (profiles.clone(), props.mods.clone()));
 
     match profiles.as_ref() {
         Status::Loading => html! {
@@ -35,9 +38,13 @@ pub fn profiles(props: &ProfilesProps) -> Html {
             let toggle_open = {
                 let profile_create_new = props.profile_create_new.clone();
                 let profile_open = props.profile_open.clone();
+                let profile_renaming = profile_renaming.clone();
+                let profile_deleting = profile_deleting.clone();
                 move |_: MouseEvent| {
                     profile_open.set(!*profile_open);
                     profile_create_new.set(false);
+                    profile_renaming.set(false);
+                    profile_deleting.set(false);
                 }
             };
 
@@ -60,7 +67,7 @@ pub fn profiles(props: &ProfilesProps) -> Html {
                         ModManager::get_mods(mods.clone());
                     }
                 };
-                html! { <div class="profile make-element-button" onclick={onclick}>{profile}</div>}
+                html! { <div class="profile make-element-button" onclick={onclick}>{profile_label(profiles_ref, *index, profile)}</div>}
             }).collect::<Html>();
 
             let open_create_profile = {
@@ -79,31 +86,128 @@ pub fn profiles(props: &ProfilesProps) -> Html {
                 move |_: MouseEvent| {
                     let Some(profile) = DomHelper::read_input("new-profile-input") else { return };
                     if !profile.is_empty() {
-                        ModManager::create_profile(profile);
                         profile_create_new.set(false);
                         profile_open.set(false);
-                        ModManager::get_profiles(profiles.clone());
                         selected_mod.set(None);
-                        ModManager::get_mods(mods.clone());
+                        ModManager::create_profile(profile, profiles.clone(), mods.clone());
+                    }
+                }
+            };
+
+            let current_profile = profiles_ref.current_profile;
+            let current_profile_name = profiles_ref.profiles[&current_profile].clone();
+
+            let open_rename_profile = {
+                let profile_renaming = profile_renaming.clone();
+                move |_: MouseEvent| {
+                    profile_renaming.set(true);
+                }
+            };
+
+            let rename_profile = {
+                let profile_renaming = profile_renaming.clone();
+                let profiles = profiles.clone();
+                move |_: MouseEvent| {
+                    let Some(name) = DomHelper::read_input("rename-profile-input") else { return };
+                    if !name.is_empty() {
+                        ModManager::rename_profile(current_profile, name, profiles.clone());
+                        profile_renaming.set(false);
                     }
                 }
             };
 
+            let duplicate_profile = {
+                let profile_open = props.profile_open.clone();
+                let profiles = profiles.clone();
+                let selected_mod = props.selected_mod.clone();
+                let mods = props.mods.clone();
+                let name = format!("{current_profile_name} Copy");
+                move |_: MouseEvent| {
+                    profile_open.set(false);
+                    selected_mod.set(None);
+                    ModManager::duplicate_profile(current_profile, name.clone(), profiles.clone(), mods.clone());
+                }
+            };
+
+            let add_overlay_profile = {
+                let profile_open = props.profile_open.clone();
+                let profiles = profiles.clone();
+                let selected_mod = props.selected_mod.clone();
+                let mods = props.mods.clone();
+                let name = format!("{current_profile_name} Overlay");
+                move |_: MouseEvent| {
+                    profile_open.set(false);
+                    selected_mod.set(None);
+                    ModManager::add_child_profile(current_profile, name.clone(), profiles.clone(), mods.clone());
+                }
+            };
+
+            // The profile keeps its current mods but no longer follows changes to its parent
+            let detach_profile = {
+                let profiles = profiles.clone();
+                move |_: MouseEvent| {
+                    ModManager::set_profile_parent(current_profile, None, profiles.clone());
+                }
+            };
+
+            let delete_profile = {
+                let profile_open = props.profile_open.clone();
+                let profile_deleting = profile_deleting.clone();
+                let profiles = profiles.clone();
+                let selected_mod = props.selected_mod.clone();
+                let mods = props.mods.clone();
+                move |_: MouseEvent| {
+                    // Deleting removes mod data only this profile uses from the library, so ask first
+                    if !*profile_deleting {
+                        profile_deleting.set(true);
+                        return;
+                    }
+                    profile_deleting.set(false);
+                    profile_open.set(false);
+                    selected_mod.set(None);
+                    mods.set(Status::Loading);
+                    ModManager::delete_profile(current_profile, profiles.clone(), mods.clone());
+                }
+            };
 
             html! {
                 <div class="element profiles">
                     if props.disabled {
                         <div class="selected-profile make-element-disabled">
-                            {format!("Profile: {}", profiles_ref.profiles[&profiles_ref.current_profile])}
+                            {format!("Profile: {}", profile_label(profiles_ref, current_profile, &current_profile_name))}
                         </div>
                     } else {
                         <div class={classes!("selected-profile", "make-element-button", if props.disabled { Some("make-element-disabled") } else { None })} onclick={toggle_open}>
-                            {format!("Profile: {}", profiles_ref.profiles[&profiles_ref.current_profile])}
+                            {format!("Profile: {}", profile_label(profiles_ref, current_profile, &current_profile_name))}
                         </div>
                     }
 
                     if *props.profile_open {
                         <div class="profiles-list">
+                            if *profile_renaming {
+                                <div class="new-profile">
+                                    <input id="rename-profile-input" type="text" class="profile new-profile-input" value={current_profile_name} />
+                                    <Button onclick={rename_profile}>{"Submit"}</Button>
+                                </div>
+                            } else {
+                                <div class="profile-actions">
+                                    <div class="profile make-element-button" onclick={open_rename_profile}>{"Rename"}</div>
+                                    <div class="profile make-element-button" onclick={duplicate_profile}>{"Duplicate"}</div>
+                                    <div class="profile make-element-button" onclick={add_overlay_profile}>{"Overlay"}</div>
+                                    if profiles_ref.parents.contains_key(&current_profile) {
+                                        <div class="profile make-element-button" onclick={detach_profile}>{"Detach"}</div>
+                                    }
+                                    if profiles_ref.profiles.len() > 1 {
+                                        <div class="profile make-element-button" onclick={delete_profile}>
+                                            if *profile_deleting {
+                                                {"Confirm"}
+                                            } else {
+                                                {"Delete"}
+                                            }
+                                        </div>
+                                    }
+                                </div>
+                            }
                             {profiles_list_html}
                             if *props.profile_create_new {
                                 <div class="new-profile">
@@ -120,4 +224,12 @@ pub fn profiles(props: &ProfilesProps) -> Html {
         },
         Status::Error(_) => unimplemented!()
     }
-}
\ No newline at end of file
+}
+
+/// Profiles inheriting mods name the profile they inherit from
+fn profile_label(profiles: &models::Profiles, index: usize, name: &str) -> String {
+    match profiles.parents.get(&index).and_then(|parent| profiles.profiles.get(parent)) {
+        Some(parent_name) => format!("{name} (based on {parent_name})"),
+        None => name.to_string(),
+    }
+}